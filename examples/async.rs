@@ -1,6 +1,6 @@
 extern crate tokio_1 as tokio;
 
-use backoff::future::retry;
+use backoff::tokio::retry;
 use backoff::ExponentialBackoff;
 
 async fn fetch_url(url: &str) -> Result<String, reqwest::Error> {
@@ -0,0 +1,56 @@
+//! Retrying futures on the [async-std](https://crates.io/crates/async-std) runtime.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Duration;
+
+use crate::backoff::Backoff;
+use crate::error::Error;
+use crate::future::{retry_notify_with_sleeper, Retry, Sleeper};
+use crate::retry::{NoopNotify, Notify};
+
+/// [`Sleeper`] backed by [`async_std::task::sleep`](https://docs.rs/async-std/1/async_std/task/fn.sleep.html).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AsyncStdSleeper;
+
+impl Sleeper for AsyncStdSleeper {
+    type Sleep = Pin<Box<dyn Future<Output = ()> + Send + 'static>>;
+    fn sleep(&self, dur: Duration) -> Self::Sleep {
+        Box::pin(::async_std_1::task::sleep(dur))
+    }
+}
+
+/// Retries given `operation` according to the [`Backoff`] policy on the async-std runtime.
+/// [`Backoff`] is reset before it is used.
+///
+/// See [`crate::future::retry`].
+pub fn retry<I, E, Fn, Fut, B>(
+    backoff: B,
+    operation: Fn,
+) -> Retry<AsyncStdSleeper, B, NoopNotify, Fn, Fut>
+where
+    B: Backoff,
+    Fn: FnMut() -> Fut,
+    Fut: Future<Output = Result<I, Error<E>>>,
+{
+    retry_notify(backoff, operation, NoopNotify)
+}
+
+/// Retries given `operation` according to the [`Backoff`] policy on the async-std runtime.
+/// Calls `notify` on failed attempts (in case of [`Error::Transient`]).
+/// [`Backoff`] is reset before it is used.
+///
+/// See [`crate::future::retry_notify`].
+pub fn retry_notify<I, E, Fn, Fut, B, N>(
+    backoff: B,
+    operation: Fn,
+    notify: N,
+) -> Retry<AsyncStdSleeper, B, N, Fn, Fut>
+where
+    B: Backoff,
+    Fn: FnMut() -> Fut,
+    Fut: Future<Output = Result<I, Error<E>>>,
+    N: Notify<E>,
+{
+    retry_notify_with_sleeper(AsyncStdSleeper, backoff, operation, notify)
+}
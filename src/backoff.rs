@@ -1,5 +1,10 @@
+use instant::Instant;
 use std::time::Duration;
 
+use crate::clock::Clock;
+use crate::default;
+use crate::exponential::{duration_to_nanos, nanos_to_duration};
+
 /// `Backoff` is a backoff policy for retrying an operation.
 pub trait Backoff {
     /// Resets the internal state to the initial value.
@@ -62,3 +67,562 @@ impl Backoff for Constant {
         Some(self.interval)
     }
 }
+
+/// Extension methods for composing [`Backoff`](trait.Backoff.html) policies.
+///
+/// # Examples
+///
+/// Three quick retries at 50 milliseconds, then exponential backoff capped at 30 seconds,
+/// with at most 10 retries altogether:
+///
+/// ```rust
+/// # use backoff::backoff::{BackoffExt, Constant};
+/// # use backoff::ExponentialBackoff;
+/// # use std::time::Duration;
+/// let backoff = Constant::new(Duration::from_millis(50))
+///     .take(3)
+///     .chain(ExponentialBackoff::default().cap(Duration::from_secs(30)))
+///     .take(10);
+/// ```
+pub trait BackoffExt: Backoff + Sized {
+    /// Gives up after `max_retries` retries, see [`MaxRetries`](struct.MaxRetries.html).
+    fn with_max_retries(self, max_retries: usize) -> MaxRetries<Self> {
+        MaxRetries::new(self, max_retries)
+    }
+
+    /// Gives up after `n` retries. Same as [`with_max_retries`](#method.with_max_retries).
+    fn take(self, n: usize) -> MaxRetries<Self> {
+        MaxRetries::new(self, n)
+    }
+
+    /// Clamps every delay to at most `max`.
+    fn cap(self, max: Duration) -> Cap<Self> {
+        Cap { backoff: self, max }
+    }
+
+    /// Clamps every delay to at least `min`.
+    fn floor(self, min: Duration) -> Floor<Self> {
+        Floor { backoff: self, min }
+    }
+
+    /// Switches to `other` once this policy is exhausted.
+    fn chain<B: Backoff>(self, other: B) -> Chain<Self, B> {
+        Chain {
+            first: self,
+            second: other,
+            first_done: false,
+        }
+    }
+
+    /// Transforms every delay with `f`.
+    fn map<F>(self, f: F) -> Map<Self, F>
+    where
+        F: FnMut(Duration) -> Duration,
+    {
+        Map { backoff: self, f }
+    }
+
+    /// Randomizes every delay in the range of `randomization_factor` percentage below and
+    /// above it.
+    ///
+    /// A randomization factor of 0.5 results in a random period ranging between 50% below and 50%
+    /// above the delay.
+    fn with_jitter(self, randomization_factor: f64) -> Jittered<Self> {
+        Jittered {
+            backoff: self,
+            randomization_factor,
+        }
+    }
+}
+
+impl<B: Backoff> BackoffExt for B {}
+
+/// MaxRetries limits the number of retries of a backoff policy.
+///
+/// [`next_backoff`](trait.Backoff.html#tymethod.next_backoff) returns `None` once it has been
+/// called `max_retries` times since the creation or the last [`reset`](trait.Backoff.html#method.reset).
+#[derive(Debug, Clone)]
+pub struct MaxRetries<B> {
+    backoff: B,
+    max_retries: usize,
+    retries: usize,
+}
+
+impl<B> MaxRetries<B> {
+    /// Creates a new MaxRetries policy which allows at most `max_retries` retries
+    /// of `backoff`.
+    pub fn new(backoff: B, max_retries: usize) -> MaxRetries<B> {
+        MaxRetries {
+            backoff,
+            max_retries,
+            retries: 0,
+        }
+    }
+
+    /// Returns the wrapped backoff policy.
+    pub fn into_inner(self) -> B {
+        self.backoff
+    }
+}
+
+impl<B: Backoff> Backoff for MaxRetries<B> {
+    fn reset(&mut self) {
+        self.retries = 0;
+        self.backoff.reset();
+    }
+
+    fn next_backoff(&mut self) -> Option<Duration> {
+        if self.retries >= self.max_retries {
+            return None;
+        }
+        self.retries += 1;
+        self.backoff.next_backoff()
+    }
+}
+
+/// Cap clamps the delays of a backoff policy to a maximum, see
+/// [`BackoffExt::cap`](trait.BackoffExt.html#method.cap).
+#[derive(Debug, Clone)]
+pub struct Cap<B> {
+    backoff: B,
+    max: Duration,
+}
+
+impl<B: Backoff> Backoff for Cap<B> {
+    fn reset(&mut self) {
+        self.backoff.reset();
+    }
+
+    fn next_backoff(&mut self) -> Option<Duration> {
+        self.backoff.next_backoff().map(|d| d.min(self.max))
+    }
+}
+
+/// Floor clamps the delays of a backoff policy to a minimum, see
+/// [`BackoffExt::floor`](trait.BackoffExt.html#method.floor).
+#[derive(Debug, Clone)]
+pub struct Floor<B> {
+    backoff: B,
+    min: Duration,
+}
+
+impl<B: Backoff> Backoff for Floor<B> {
+    fn reset(&mut self) {
+        self.backoff.reset();
+    }
+
+    fn next_backoff(&mut self) -> Option<Duration> {
+        self.backoff.next_backoff().map(|d| d.max(self.min))
+    }
+}
+
+/// Chain uses a second backoff policy once the first one is exhausted, see
+/// [`BackoffExt::chain`](trait.BackoffExt.html#method.chain).
+#[derive(Debug, Clone)]
+pub struct Chain<A, B> {
+    first: A,
+    second: B,
+    first_done: bool,
+}
+
+impl<A: Backoff, B: Backoff> Backoff for Chain<A, B> {
+    fn reset(&mut self) {
+        self.first.reset();
+        self.second.reset();
+        self.first_done = false;
+    }
+
+    fn next_backoff(&mut self) -> Option<Duration> {
+        if !self.first_done {
+            match self.first.next_backoff() {
+                Some(d) => return Some(d),
+                None => self.first_done = true,
+            }
+        }
+        self.second.next_backoff()
+    }
+}
+
+/// Map transforms the delays of a backoff policy, see
+/// [`BackoffExt::map`](trait.BackoffExt.html#method.map).
+#[derive(Debug, Clone)]
+pub struct Map<B, F> {
+    backoff: B,
+    f: F,
+}
+
+impl<B, F> Backoff for Map<B, F>
+where
+    B: Backoff,
+    F: FnMut(Duration) -> Duration,
+{
+    fn reset(&mut self) {
+        self.backoff.reset();
+    }
+
+    fn next_backoff(&mut self) -> Option<Duration> {
+        self.backoff.next_backoff().map(&mut self.f)
+    }
+}
+
+/// Jittered randomizes the delays of a backoff policy, see
+/// [`BackoffExt::with_jitter`](trait.BackoffExt.html#method.with_jitter).
+#[derive(Debug, Clone)]
+pub struct Jittered<B> {
+    backoff: B,
+    randomization_factor: f64,
+}
+
+impl<B> Jittered<B> {
+    fn get_random_value_from_interval(
+        randomization_factor: f64,
+        random: f64,
+        interval: Duration,
+    ) -> Duration {
+        let factor = 1.0 - randomization_factor + 2.0 * randomization_factor * random;
+        // Saturates at `Duration::MAX`, unlike `Duration::mul_f64`.
+        nanos_to_duration(duration_to_nanos(interval) * factor.max(0.0))
+    }
+}
+
+impl<B: Backoff> Backoff for Jittered<B> {
+    fn reset(&mut self) {
+        self.backoff.reset();
+    }
+
+    fn next_backoff(&mut self) -> Option<Duration> {
+        self.backoff.next_backoff().map(|d| {
+            let random = rand::random::<f64>();
+            Self::get_random_value_from_interval(self.randomization_factor, random, d)
+        })
+    }
+}
+
+/// Decorrelated jitter is a backoff policy where each delay is picked at random
+/// between `initial_interval` and three times the previous delay, capped by `max_interval`.
+///
+/// Compared to [`ExponentialBackoff`](../exponential/struct.ExponentialBackoff.html) the
+/// delays of many clients spread out quicker, as each delay depends on the client's own
+/// previous one:
+///
+///```text
+///  delay = min(max_interval, random value in range [initial_interval, previous delay * 3])
+///```
+///
+/// If the time elapsed since a [`DecorrelatedJitter`](struct.DecorrelatedJitter.html) instance
+/// is created goes past the `max_elapsed_time`, then [`next_backoff`](trait.Backoff.html#tymethod.next_backoff)
+/// starts returning `None`.
+#[derive(Debug)]
+pub struct DecorrelatedJitter<C> {
+    /// The previous delay. The next delay is picked from a range based on it.
+    pub current_interval: Duration,
+    /// The lower bound of every delay, also the delay the policy starts with.
+    pub initial_interval: Duration,
+    /// The maximum value of the back off period.
+    pub max_interval: Duration,
+    /// The system time. It is calculated when a [`DecorrelatedJitter`](struct.DecorrelatedJitter.html) instance is
+    /// created and is reset when [`reset`](trait.Backoff.html#method.reset) is called.
+    pub start_time: Instant,
+    /// The maximum elapsed time after instantiating [`DecorrelatedJitter`](struct.DecorrelatedJitter.html) or calling
+    /// [`reset`](trait.Backoff.html#method.reset) after which [`next_backoff`](trait.Backoff.html#tymethod.next_backoff) returns `None`.
+    pub max_elapsed_time: Option<Duration>,
+    /// The clock used to get the current time.
+    pub clock: C,
+}
+
+impl<C> Default for DecorrelatedJitter<C>
+where
+    C: Clock + Default,
+{
+    fn default() -> DecorrelatedJitter<C> {
+        let mut dj = DecorrelatedJitter {
+            current_interval: Duration::from_millis(default::INITIAL_INTERVAL_MILLIS),
+            initial_interval: Duration::from_millis(default::INITIAL_INTERVAL_MILLIS),
+            max_interval: Duration::from_millis(default::MAX_INTERVAL_MILLIS),
+            max_elapsed_time: Some(Duration::from_millis(default::MAX_ELAPSED_TIME_MILLIS)),
+            clock: C::default(),
+            start_time: Instant::now(),
+        };
+        dj.reset();
+        dj
+    }
+}
+
+impl<C: Clock> DecorrelatedJitter<C> {
+    /// Returns the elapsed time since start_time.
+    pub fn get_elapsed_time(&self) -> Duration {
+        self.clock.now().duration_since(self.start_time)
+    }
+
+    fn get_random_value_from_interval(
+        initial_interval: Duration,
+        random: f64,
+        current_interval: Duration,
+    ) -> Duration {
+        let upper = current_interval.saturating_mul(3);
+        if upper <= initial_interval {
+            return initial_interval;
+        }
+        initial_interval.saturating_add((upper - initial_interval).mul_f64(random))
+    }
+}
+
+impl<C> Backoff for DecorrelatedJitter<C>
+where
+    C: Clock,
+{
+    fn reset(&mut self) {
+        self.current_interval = self.initial_interval;
+        self.start_time = self.clock.now();
+    }
+
+    fn next_backoff(&mut self) -> Option<Duration> {
+        let elapsed_time = self.get_elapsed_time();
+
+        match self.max_elapsed_time {
+            Some(v) if elapsed_time > v => None,
+            _ => {
+                let random = rand::random::<f64>();
+                let interval = Self::get_random_value_from_interval(
+                    self.initial_interval,
+                    random,
+                    self.current_interval,
+                )
+                .min(self.max_interval);
+                self.current_interval = interval;
+
+                match self.max_elapsed_time {
+                    Some(v) if elapsed_time.saturating_add(interval) > v => None,
+                    _ => Some(interval),
+                }
+            }
+        }
+    }
+}
+
+impl<C> Clone for DecorrelatedJitter<C>
+where
+    C: Clone,
+{
+    fn clone(&self) -> Self {
+        let clock = self.clock.clone();
+        DecorrelatedJitter { clock, ..*self }
+    }
+}
+
+/// Builder for [`DecorrelatedJitter`](struct.DecorrelatedJitter.html).
+#[derive(Debug)]
+pub struct DecorrelatedJitterBuilder<C> {
+    initial_interval: Duration,
+    max_interval: Duration,
+    max_elapsed_time: Option<Duration>,
+    clock: C,
+}
+
+impl<C> Default for DecorrelatedJitterBuilder<C>
+where
+    C: Default,
+{
+    fn default() -> Self {
+        Self {
+            initial_interval: Duration::from_millis(default::INITIAL_INTERVAL_MILLIS),
+            max_interval: Duration::from_millis(default::MAX_INTERVAL_MILLIS),
+            max_elapsed_time: Some(Duration::from_millis(default::MAX_ELAPSED_TIME_MILLIS)),
+            clock: C::default(),
+        }
+    }
+}
+
+impl<C> DecorrelatedJitterBuilder<C>
+where
+    C: Clock + Default,
+{
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
+impl<C> DecorrelatedJitterBuilder<C> {
+    /// The lower bound of every delay, also the delay the policy starts with.
+    pub fn with_initial_interval(&mut self, initial_interval: Duration) -> &mut Self {
+        self.initial_interval = initial_interval;
+        self
+    }
+
+    /// The maximum value of the back off period.
+    pub fn with_max_interval(&mut self, max_interval: Duration) -> &mut Self {
+        self.max_interval = max_interval;
+        self
+    }
+
+    /// The maximum elapsed time after instantiating [`DecorrelatedJitter`](struct.DecorrelatedJitter.html) or calling
+    /// [`reset`](trait.Backoff.html#method.reset) after which [`next_backoff`](trait.Backoff.html#tymethod.next_backoff) returns `None`.
+    pub fn with_max_elapsed_time(&mut self, max_elapsed_time: Option<Duration>) -> &mut Self {
+        self.max_elapsed_time = max_elapsed_time;
+        self
+    }
+
+    /// The clock used to get the current time.
+    ///
+    /// Every built [`DecorrelatedJitter`](struct.DecorrelatedJitter.html) gets a clone of it,
+    /// and its `start_time` is taken from it.
+    pub fn with_clock<D>(&self, clock: D) -> DecorrelatedJitterBuilder<D>
+    where
+        D: Clock + Clone,
+    {
+        DecorrelatedJitterBuilder {
+            initial_interval: self.initial_interval,
+            max_interval: self.max_interval,
+            max_elapsed_time: self.max_elapsed_time,
+            clock,
+        }
+    }
+
+    pub fn build(&self) -> DecorrelatedJitter<C>
+    where
+        C: Clock + Clone,
+    {
+        let clock = self.clock.clone();
+        DecorrelatedJitter {
+            current_interval: self.initial_interval,
+            initial_interval: self.initial_interval,
+            max_interval: self.max_interval,
+            max_elapsed_time: self.max_elapsed_time,
+            start_time: clock.now(),
+            clock,
+        }
+    }
+}
+
+#[cfg(test)]
+use crate::clock::SystemClock;
+
+#[test]
+fn max_retries() {
+    let mut backoff = Zero {}.with_max_retries(2);
+    assert_eq!(Some(Duration::default()), backoff.next_backoff());
+    assert_eq!(Some(Duration::default()), backoff.next_backoff());
+    assert_eq!(None, backoff.next_backoff());
+
+    backoff.reset();
+    assert_eq!(Some(Duration::default()), backoff.next_backoff());
+}
+
+#[test]
+fn cap_and_floor() {
+    let mut backoff = Constant::new(Duration::from_secs(5)).cap(Duration::from_secs(1));
+    assert_eq!(Some(Duration::from_secs(1)), backoff.next_backoff());
+
+    let mut backoff = Zero {}.floor(Duration::from_secs(1));
+    assert_eq!(Some(Duration::from_secs(1)), backoff.next_backoff());
+
+    let mut backoff = Stop {}.floor(Duration::from_secs(1));
+    assert_eq!(None, backoff.next_backoff());
+}
+
+#[test]
+fn chain() {
+    let fast = Duration::from_millis(50);
+    let slow = Duration::from_secs(1);
+    let mut backoff = Constant::new(fast)
+        .take(2)
+        .chain(Constant::new(slow))
+        .take(3);
+
+    assert_eq!(Some(fast), backoff.next_backoff());
+    assert_eq!(Some(fast), backoff.next_backoff());
+    assert_eq!(Some(slow), backoff.next_backoff());
+    assert_eq!(None, backoff.next_backoff());
+
+    backoff.reset();
+    assert_eq!(Some(fast), backoff.next_backoff());
+}
+
+#[test]
+fn map() {
+    let mut backoff = Constant::new(Duration::from_secs(1)).map(|d| d * 2);
+    assert_eq!(Some(Duration::from_secs(2)), backoff.next_backoff());
+}
+
+#[test]
+fn jittered_random_value() {
+    let f = Jittered::<Zero>::get_random_value_from_interval;
+    let interval = Duration::from_secs(2);
+    assert_eq!(Duration::from_secs(1), f(0.5, 0.0, interval));
+    assert_eq!(Duration::from_secs(2), f(0.5, 0.5, interval));
+    assert_eq!(Duration::from_secs(3), f(0.5, 1.0, interval));
+    // Delays never go below zero.
+    assert_eq!(Duration::from_secs(0), f(2.0, 0.0, interval));
+    // Nor overflow.
+    assert_eq!(
+        Duration::MAX.as_secs(),
+        f(0.5, 1.0, Duration::MAX).as_secs()
+    );
+}
+
+#[test]
+fn decorrelated_jitter_random_value() {
+    let f = DecorrelatedJitter::<SystemClock>::get_random_value_from_interval;
+    let base = Duration::from_secs(1);
+    assert_eq!(base, f(base, 0.0, base));
+    assert_eq!(Duration::from_secs(2), f(base, 0.5, base));
+    assert_eq!(Duration::from_secs(8), f(base, 0.5, Duration::from_secs(5)));
+    // The range never goes below the initial interval.
+    assert_eq!(base, f(base, 0.99, Duration::from_millis(100)));
+    // Nor overflows without a cap.
+    assert!(f(base, 0.99, Duration::MAX / 2) > Duration::MAX / 2);
+}
+
+#[test]
+fn decorrelated_jitter_stays_within_bounds() {
+    let initial_interval = Duration::from_millis(100);
+    let max_interval = Duration::from_secs(2);
+    let mut backoff: DecorrelatedJitter<SystemClock> = DecorrelatedJitterBuilder::new()
+        .with_initial_interval(initial_interval)
+        .with_max_interval(max_interval)
+        .with_max_elapsed_time(None)
+        .build();
+
+    for _ in 0..100 {
+        let previous = backoff.current_interval;
+        let next = backoff.next_backoff().unwrap();
+        assert!(next >= initial_interval);
+        assert!(next <= max_interval);
+        assert!(next <= previous * 3);
+    }
+
+    backoff.reset();
+    assert_eq!(backoff.current_interval, initial_interval);
+}
+
+#[test]
+fn decorrelated_jitter_without_cap() {
+    let initial_interval = Duration::MAX / 2;
+    let mut backoff: DecorrelatedJitter<SystemClock> = DecorrelatedJitterBuilder::new()
+        .with_initial_interval(initial_interval)
+        .with_max_interval(Duration::MAX)
+        .with_max_elapsed_time(Some(Duration::from_secs(60)))
+        .build();
+    assert_eq!(None, backoff.next_backoff());
+
+    backoff.max_elapsed_time = None;
+    for _ in 0..10 {
+        assert!(backoff.next_backoff().unwrap() >= initial_interval);
+    }
+}
+
+#[test]
+fn decorrelated_jitter_max_elapsed_time() {
+    let clock = SystemClock::default();
+    let mut backoff = DecorrelatedJitter {
+        max_interval: Duration::from_millis(500),
+        max_elapsed_time: Some(Duration::from_secs(1)),
+        start_time: clock.now() - Duration::from_secs(2),
+        clock,
+        ..DecorrelatedJitter::default()
+    };
+    assert_eq!(None, backoff.next_backoff());
+
+    backoff.reset();
+    assert!(backoff.next_backoff().is_some());
+}
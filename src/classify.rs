@@ -0,0 +1,138 @@
+//! Classifiers deciding whether errors are retried, for operations returning plain errors.
+//!
+//! See [`retry_if`](../fn.retry_if.html).
+
+use std::io;
+use std::time::Duration;
+
+use crate::error::Error;
+
+/// Decision tells how an error of an operation should be treated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Decision {
+    /// The error is returned immediately, see [`Error::Permanent`].
+    Permanent,
+
+    /// The operation is retried according to the backoff policy.
+    Transient,
+
+    /// The operation is retried after the given duration, see [`Error::retry_after`].
+    RetryAfter(Duration),
+}
+
+impl Decision {
+    /// Wraps `err` into the [`Error`] corresponding to the decision.
+    pub fn apply<E>(self, err: E) -> Error<E> {
+        match self {
+            Decision::Permanent => Error::Permanent(err),
+            Decision::Transient => Error::transient(err),
+            Decision::RetryAfter(duration) => Error::retry_after(err, duration),
+        }
+    }
+}
+
+/// Classify decides how the errors of an operation should be treated.
+///
+/// It's implemented for closures `Fn(&E) -> Decision`, implement it for a type to reuse the
+/// same rules across many call sites.
+pub trait Classify<E> {
+    fn classify(&self, err: &E) -> Decision;
+
+    /// Wraps `err` into the [`Error`] the classifier decides on.
+    fn error(&self, err: E) -> Error<E> {
+        self.classify(&err).apply(err)
+    }
+}
+
+impl<E, F> Classify<E> for F
+where
+    F: Fn(&E) -> Decision,
+{
+    fn classify(&self, err: &E) -> Decision {
+        self(err)
+    }
+}
+
+/// Classifier of [`io::Error`]s by their [`kind`](io::Error::kind).
+///
+/// Errors which are likely to go away, like refused or reset connections, time outs and
+/// interrupted or would-block calls are transient. Errors which retrying won't fix, like missing
+/// files, denied permissions or invalid input are permanent. Other kinds are transient, the same
+/// as with the `?` operator.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct IoClassifier;
+
+impl Classify<io::Error> for IoClassifier {
+    fn classify(&self, err: &io::Error) -> Decision {
+        match err.kind() {
+            io::ErrorKind::NotFound
+            | io::ErrorKind::PermissionDenied
+            | io::ErrorKind::InvalidInput
+            | io::ErrorKind::InvalidData
+            | io::ErrorKind::AlreadyExists
+            | io::ErrorKind::Unsupported => Decision::Permanent,
+            _ => Decision::Transient,
+        }
+    }
+}
+
+/// Wraps `err` into the [`Error`] [`IoClassifier`] decides on. Use it instead of the `?`
+/// operator's conversion, which always produces transient errors.
+///
+/// # Examples
+///
+/// ```rust
+/// # use backoff::{classify, retry, Error, ExponentialBackoff};
+/// # use std::fs::File;
+/// let op = || -> Result<File, Error<std::io::Error>> {
+///     let file = File::open("/does/not/exist").map_err(classify::io)?;
+///     Ok(file)
+/// };
+///
+/// match retry(ExponentialBackoff::default(), op) {
+///     Err(Error::Permanent(err)) => assert_eq!(err.kind(), std::io::ErrorKind::NotFound),
+///     _ => unreachable!(),
+/// }
+/// ```
+pub fn io(err: io::Error) -> Error<io::Error> {
+    IoClassifier.error(err)
+}
+
+#[test]
+fn io_classifier() {
+    let transient = [
+        io::ErrorKind::ConnectionRefused,
+        io::ErrorKind::ConnectionReset,
+        io::ErrorKind::TimedOut,
+        io::ErrorKind::Interrupted,
+        io::ErrorKind::WouldBlock,
+    ];
+    for kind in transient {
+        assert_eq!(IoClassifier.classify(&kind.into()), Decision::Transient);
+    }
+
+    let permanent = [
+        io::ErrorKind::NotFound,
+        io::ErrorKind::PermissionDenied,
+        io::ErrorKind::InvalidInput,
+    ];
+    for kind in permanent {
+        assert_eq!(IoClassifier.classify(&kind.into()), Decision::Permanent);
+    }
+}
+
+#[test]
+fn closure_classifier() {
+    let classify = |err: &u32| match *err {
+        0 => Decision::Permanent,
+        1 => Decision::Transient,
+        n => Decision::RetryAfter(Duration::from_secs(u64::from(n))),
+    };
+
+    assert_eq!(classify.error(0), Error::Permanent(0));
+    assert_eq!(classify.error(1), Error::transient(1));
+    assert_eq!(
+        classify.error(2),
+        Error::retry_after(2, Duration::from_secs(2))
+    );
+}
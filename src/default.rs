@@ -1,4 +1,4 @@
-//! Constants for the exponential backoff policy.
+//! Constants for the exponential and decorrelated jitter backoff policies.
 
 /// The default initial interval value in milliseconds (0.5 seconds).
 pub const INITIAL_INTERVAL_MILLIS: u64 = 500;
@@ -140,6 +140,218 @@ where
     }
 }
 
+/// TimeoutError is the error of an attempt which may time out, see
+/// [`future::timeout`](future/fn.timeout.html).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TimeoutError<E> {
+    /// The attempt didn't complete within the given duration and was cancelled.
+    TimedOut(Duration),
+
+    /// The operation failed with an error.
+    Operation(E),
+}
+
+impl<E> fmt::Display for TimeoutError<E>
+where
+    E: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            TimeoutError::TimedOut(ref timeout) => {
+                write!(f, "attempt timed out after {:?}", timeout)
+            }
+            TimeoutError::Operation(ref err) => err.fmt(f),
+        }
+    }
+}
+
+impl<E> error::Error for TimeoutError<E>
+where
+    E: error::Error,
+{
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            TimeoutError::TimedOut(_) => None,
+            TimeoutError::Operation(ref err) => err.source(),
+        }
+    }
+}
+
+/// RetryResult is the outcome of a retry loop, the same for the blocking and the async ones.
+pub type RetryResult<T, E> = Result<T, RetryError<E>>;
+
+/// RetryError reports why a retry loop gave up, see
+/// [`Retry::retry_report`](struct.Retry.html#method.retry_report) and
+/// [`future::Retry::report`](future/struct.Retry.html#method.report).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryError<E> {
+    /// The error of the last attempt.
+    pub err: E,
+
+    /// Why the loop stopped.
+    pub reason: StopReason,
+
+    /// The number of attempts, including the last one.
+    pub attempts: u32,
+
+    /// The time elapsed since the first attempt was started.
+    pub elapsed: Duration,
+
+    /// The errors of the attempts before the last one, oldest first. Only recorded on request,
+    /// empty otherwise.
+    pub history: Vec<E>,
+}
+
+impl<E> RetryError<E> {
+    pub(crate) fn new(err: E, reason: StopReason, attempts: u32, elapsed: Duration) -> Self {
+        RetryError {
+            err,
+            reason,
+            attempts,
+            elapsed,
+            history: Vec::new(),
+        }
+    }
+
+    /// Returns the error of the last attempt, which is what `future::retry` resolves to.
+    pub fn into_inner(self) -> E {
+        self.err
+    }
+
+    /// Returns the error of the last attempt the way [`retry`](fn.retry.html) returns it:
+    /// [`Error::Permanent`] if the loop stopped on a permanent error, else [`Error::Transient`].
+    pub fn into_error(self) -> Error<E> {
+        match self.reason {
+            StopReason::Permanent => Error::Permanent(self.err),
+            _ => Error::transient(self.err),
+        }
+    }
+}
+
+impl<E> fmt::Display for RetryError<E>
+where
+    E: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(
+            f,
+            "{} after {} attempt(s) in {:?}: {}",
+            self.reason, self.attempts, self.elapsed, self.err
+        )
+    }
+}
+
+impl<E> error::Error for RetryError<E>
+where
+    E: error::Error,
+{
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        self.err.source()
+    }
+}
+
+/// StopReason tells why a retry loop gave up, see [`RetryError`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopReason {
+    /// The last attempt failed with [`Error::Permanent`].
+    Permanent,
+
+    /// The backoff policy ran out of retries, or out of its elapsed time.
+    Exhausted,
+
+    /// The deadline of the loop passed, or the next attempt would have started after it.
+    Deadline,
+
+    /// The loop was cancelled.
+    Cancelled,
+}
+
+impl fmt::Display for StopReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        f.write_str(match *self {
+            StopReason::Permanent => "permanent error",
+            StopReason::Exhausted => "retries exhausted",
+            StopReason::Deadline => "deadline exceeded",
+            StopReason::Cancelled => "cancelled",
+        })
+    }
+}
+
+/// CancelError is the error of a retry loop which can be cancelled, see
+/// [`Retry::retry_cancellable`](struct.Retry.html#method.retry_cancellable) and
+/// [`future::Retry::with_cancel`](future/struct.Retry.html#method.with_cancel).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CancelError<E> {
+    /// The loop was cancelled before the next attempt. Holds the error of the last one.
+    Cancelled(E),
+
+    /// The loop gave up on its own, the same way as if it couldn't be cancelled.
+    Failed(E),
+}
+
+impl<E> fmt::Display for CancelError<E>
+where
+    E: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            CancelError::Cancelled(ref err) => write!(f, "retry cancelled, last error: {}", err),
+            CancelError::Failed(ref err) => err.fmt(f),
+        }
+    }
+}
+
+impl<E> error::Error for CancelError<E>
+where
+    E: error::Error,
+{
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            CancelError::Cancelled(ref err) | CancelError::Failed(ref err) => err.source(),
+        }
+    }
+}
+
+/// DeadlineError is the error of an async retry loop with a deadline, see
+/// [`future::Retry::with_deadline`](future/struct.Retry.html#method.with_deadline).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeadlineError<E> {
+    /// The deadline passed. Holds the error of the last attempt, unless the attempt was still
+    /// running at the deadline and got cancelled, or its error was already notified and the
+    /// loop was waiting for the next attempt.
+    Exceeded(Option<E>),
+
+    /// The loop gave up before the deadline.
+    Failed(E),
+}
+
+impl<E> fmt::Display for DeadlineError<E>
+where
+    E: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            DeadlineError::Exceeded(Some(ref err)) => {
+                write!(f, "retry deadline exceeded, last error: {}", err)
+            }
+            DeadlineError::Exceeded(None) => write!(f, "retry deadline exceeded"),
+            DeadlineError::Failed(ref err) => err.fmt(f),
+        }
+    }
+}
+
+impl<E> error::Error for DeadlineError<E>
+where
+    E: error::Error,
+{
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            DeadlineError::Exceeded(Some(ref err)) | DeadlineError::Failed(ref err) => err.source(),
+            DeadlineError::Exceeded(None) => None,
+        }
+    }
+}
+
 #[test]
 fn create_permanent_error() {
     let e = Error::permanent("err");
@@ -170,3 +382,53 @@ fn create_transient_error_with_retry_after() {
         }
     );
 }
+
+#[test]
+fn display_retry_error() {
+    let e = RetryError::new("err", StopReason::Exhausted, 3, Duration::from_secs(2));
+    assert_eq!(
+        e.to_string(),
+        "retries exhausted after 3 attempt(s) in 2s: err"
+    );
+}
+
+#[test]
+fn retry_error_source() {
+    use std::error::Error as _;
+
+    #[derive(Debug)]
+    struct Inner(fmt::Error);
+
+    impl fmt::Display for Inner {
+        fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+            f.write_str("inner")
+        }
+    }
+
+    impl error::Error for Inner {
+        fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+            Some(&self.0)
+        }
+    }
+
+    // The inner error is already displayed, the chain goes on with its source.
+    let e = RetryError::new(Inner(fmt::Error), StopReason::Exhausted, 1, Duration::ZERO);
+    assert!(e.source().unwrap().is::<fmt::Error>());
+}
+
+#[test]
+fn display_deadline_error() {
+    let e = DeadlineError::Exceeded(Some("err"));
+    assert_eq!(e.to_string(), "retry deadline exceeded, last error: err");
+    let e = DeadlineError::<&str>::Exceeded(None);
+    assert_eq!(e.to_string(), "retry deadline exceeded");
+}
+
+#[test]
+fn retry_error_into_error() {
+    let elapsed = Duration::from_secs(1);
+    let e = RetryError::new("err", StopReason::Permanent, 1, elapsed);
+    assert_eq!(e.into_error(), Error::Permanent("err"));
+    let e = RetryError::new("err", StopReason::Deadline, 2, elapsed);
+    assert_eq!(e.into_error(), Error::transient("err"));
+}
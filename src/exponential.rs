@@ -1,5 +1,6 @@
 use instant::Instant;
-use std::marker::PhantomData;
+use rand::rngs::StdRng;
+use rand::{Rng, RngCore, SeedableRng};
 use std::time::Duration;
 
 use crate::backoff::Backoff;
@@ -7,7 +8,7 @@ use crate::clock::Clock;
 use crate::default;
 
 #[derive(Debug)]
-pub struct ExponentialBackoff<C> {
+pub struct ExponentialBackoff<C, R = ThreadLocalRng> {
     /// The current retry interval.
     pub current_interval: Duration,
     /// The initial retry interval.
@@ -19,6 +20,8 @@ pub struct ExponentialBackoff<C> {
     pub randomization_factor: f64,
     /// The value to multiply the current interval with for each retry attempt.
     pub multiplier: f64,
+    /// The strategy used to randomize the retry interval.
+    pub jitter: Jitter,
     /// The maximum value of the back off period. Once the retry interval reaches this
     /// value it stops increasing.
     pub max_interval: Duration,
@@ -30,6 +33,49 @@ pub struct ExponentialBackoff<C> {
     pub max_elapsed_time: Option<Duration>,
     /// The clock used to get the current time.
     pub clock: C,
+    /// The random number generator used to randomize the retry interval.
+    pub rng: R,
+}
+
+/// Random number generator backed by [`rand::thread_rng`], the default of
+/// [`ExponentialBackoff`](struct.ExponentialBackoff.html).
+///
+/// Unlike `rand::rngs::ThreadRng`, it can be sent across threads.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ThreadLocalRng;
+
+impl RngCore for ThreadLocalRng {
+    fn next_u32(&mut self) -> u32 {
+        rand::thread_rng().next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        rand::thread_rng().next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        rand::thread_rng().fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        rand::thread_rng().try_fill_bytes(dest)
+    }
+}
+
+/// Jitter strategy of [`ExponentialBackoff`](struct.ExponentialBackoff.html), it decides
+/// how the retry interval is randomized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Jitter {
+    /// A random value in the range of `randomization_factor` percentage below and
+    /// above the retry interval. This is the default.
+    #[default]
+    Symmetric,
+    /// A random value between zero and the retry interval.
+    Full,
+    /// Half of the retry interval plus a random value between zero and the other half.
+    Equal,
+    /// The retry interval is used as is, `randomization_factor` is ignored.
+    None,
 }
 
 impl<C> Default for ExponentialBackoff<C>
@@ -42,9 +88,11 @@ where
             initial_interval: Duration::from_millis(default::INITIAL_INTERVAL_MILLIS),
             randomization_factor: default::RANDOMIZATION_FACTOR,
             multiplier: default::MULTIPLIER,
+            jitter: Jitter::default(),
             max_interval: Duration::from_millis(default::MAX_INTERVAL_MILLIS),
             max_elapsed_time: Some(Duration::from_millis(default::MAX_ELAPSED_TIME_MILLIS)),
             clock: C::default(),
+            rng: ThreadLocalRng,
             start_time: Instant::now(),
         };
         eb.reset();
@@ -52,7 +100,7 @@ where
     }
 }
 
-impl<C: Clock> ExponentialBackoff<C> {
+impl<C: Clock, R> ExponentialBackoff<C, R> {
     /// Returns the elapsed time since start_time.
     pub fn get_elapsed_time(&self) -> Duration {
         self.clock.now().duration_since(self.start_time)
@@ -76,6 +124,27 @@ impl<C: Clock> ExponentialBackoff<C> {
         nanos_to_duration(nanos)
     }
 
+    fn get_jittered_interval(
+        jitter: Jitter,
+        randomization_factor: f64,
+        random: f64,
+        current_interval: Duration,
+    ) -> Duration {
+        let current_interval_nanos = duration_to_nanos(current_interval);
+
+        match jitter {
+            Jitter::Symmetric => {
+                Self::get_random_value_from_interval(randomization_factor, random, current_interval)
+            }
+            Jitter::Full => nanos_to_duration(random * current_interval_nanos),
+            Jitter::Equal => {
+                let half = current_interval_nanos / 2.0;
+                nanos_to_duration(half + random * half)
+            }
+            Jitter::None => current_interval,
+        }
+    }
+
     fn increment_current_interval(&mut self) -> Duration {
         let current_interval_nanos = duration_to_nanos(self.current_interval);
         let max_interval_nanos = duration_to_nanos(self.max_interval);
@@ -89,19 +158,20 @@ impl<C: Clock> ExponentialBackoff<C> {
     }
 }
 
-fn duration_to_nanos(d: Duration) -> f64 {
+pub(crate) fn duration_to_nanos(d: Duration) -> f64 {
     d.as_secs() as f64 * 1_000_000_000.0 + f64::from(d.subsec_nanos())
 }
 
-fn nanos_to_duration(nanos: f64) -> Duration {
+pub(crate) fn nanos_to_duration(nanos: f64) -> Duration {
     let secs = nanos / 1_000_000_000.0;
     let nanos = nanos as u64 % 1_000_000_000;
     Duration::new(secs as u64, nanos as u32)
 }
 
-impl<C> Backoff for ExponentialBackoff<C>
+impl<C, R> Backoff for ExponentialBackoff<C, R>
 where
     C: Clock,
+    R: RngCore,
 {
     fn reset(&mut self) {
         self.current_interval = self.initial_interval;
@@ -114,8 +184,9 @@ where
         match self.max_elapsed_time {
             Some(v) if elapsed_time > v => None,
             _ => {
-                let random = rand::random::<f64>();
-                let randomized_interval = Self::get_random_value_from_interval(
+                let random = self.rng.gen::<f64>();
+                let randomized_interval = Self::get_jittered_interval(
+                    self.jitter,
                     self.randomization_factor,
                     random,
                     self.current_interval,
@@ -136,13 +207,19 @@ where
     }
 }
 
-impl<C> Clone for ExponentialBackoff<C>
+impl<C, R> Clone for ExponentialBackoff<C, R>
 where
     C: Clone,
+    R: Clone,
 {
     fn clone(&self) -> Self {
         let clock = self.clock.clone();
-        ExponentialBackoff { clock, ..*self }
+        let rng = self.rng.clone();
+        ExponentialBackoff {
+            clock,
+            rng,
+            ..*self
+        }
     }
 }
 
@@ -150,24 +227,31 @@ where
 ///
 /// TODO: Example
 #[derive(Debug)]
-pub struct ExponentialBackoffBuilder<C> {
+pub struct ExponentialBackoffBuilder<C, R = ThreadLocalRng> {
     initial_interval: Duration,
     randomization_factor: f64,
     multiplier: f64,
+    jitter: Jitter,
     max_interval: Duration,
     max_elapsed_time: Option<Duration>,
-    _clock: PhantomData<C>,
+    clock: C,
+    rng: R,
 }
 
-impl<C> Default for ExponentialBackoffBuilder<C> {
+impl<C> Default for ExponentialBackoffBuilder<C>
+where
+    C: Default,
+{
     fn default() -> Self {
         Self {
             initial_interval: Duration::from_millis(default::INITIAL_INTERVAL_MILLIS),
             randomization_factor: default::RANDOMIZATION_FACTOR,
             multiplier: default::MULTIPLIER,
+            jitter: Jitter::default(),
             max_interval: Duration::from_millis(default::MAX_INTERVAL_MILLIS),
             max_elapsed_time: Some(Duration::from_millis(default::MAX_ELAPSED_TIME_MILLIS)),
-            _clock: PhantomData,
+            clock: C::default(),
+            rng: ThreadLocalRng,
         }
     }
 }
@@ -179,7 +263,9 @@ where
     pub fn new() -> Self {
         Default::default()
     }
+}
 
+impl<C, R> ExponentialBackoffBuilder<C, R> {
     /// The initial retry interval.
     pub fn with_initial_interval(&mut self, initial_interval: Duration) -> &mut Self {
         self.initial_interval = initial_interval;
@@ -201,6 +287,12 @@ where
         self
     }
 
+    /// The strategy used to randomize the retry interval.
+    pub fn with_jitter(&mut self, jitter: Jitter) -> &mut Self {
+        self.jitter = jitter;
+        self
+    }
+
     /// The maximum value of the back off period. Once the retry interval reaches this
     /// value it stops increasing.
     pub fn with_max_interval(&mut self, max_interval: Duration) -> &mut Self {
@@ -215,16 +307,74 @@ where
         self
     }
 
-    pub fn build(&self) -> ExponentialBackoff<C> {
+    /// The random number generator used to randomize the retry interval.
+    ///
+    /// Every built [`ExponentialBackoff`](struct.ExponentialBackoff.html) gets a clone of it.
+    pub fn with_rng<S>(&self, rng: S) -> ExponentialBackoffBuilder<C, S>
+    where
+        C: Clone,
+        S: RngCore + Clone,
+    {
+        ExponentialBackoffBuilder {
+            initial_interval: self.initial_interval,
+            randomization_factor: self.randomization_factor,
+            multiplier: self.multiplier,
+            jitter: self.jitter,
+            max_interval: self.max_interval,
+            max_elapsed_time: self.max_elapsed_time,
+            clock: self.clock.clone(),
+            rng,
+        }
+    }
+
+    /// Randomizes the retry interval with a random number generator seeded with `seed`,
+    /// so every built [`ExponentialBackoff`](struct.ExponentialBackoff.html) returns the
+    /// same sequence of intervals.
+    pub fn with_seed(&self, seed: u64) -> ExponentialBackoffBuilder<C, StdRng>
+    where
+        C: Clone,
+    {
+        self.with_rng(StdRng::seed_from_u64(seed))
+    }
+
+    /// The clock used to get the current time.
+    ///
+    /// Every built [`ExponentialBackoff`](struct.ExponentialBackoff.html) gets a clone of it,
+    /// and its `start_time` is taken from it.
+    pub fn with_clock<D>(&self, clock: D) -> ExponentialBackoffBuilder<D, R>
+    where
+        D: Clock + Clone,
+        R: Clone,
+    {
+        ExponentialBackoffBuilder {
+            initial_interval: self.initial_interval,
+            randomization_factor: self.randomization_factor,
+            multiplier: self.multiplier,
+            jitter: self.jitter,
+            max_interval: self.max_interval,
+            max_elapsed_time: self.max_elapsed_time,
+            clock,
+            rng: self.rng.clone(),
+        }
+    }
+
+    pub fn build(&self) -> ExponentialBackoff<C, R>
+    where
+        C: Clock + Clone,
+        R: RngCore + Clone,
+    {
+        let clock = self.clock.clone();
         ExponentialBackoff {
             current_interval: self.initial_interval,
             initial_interval: self.initial_interval,
             randomization_factor: self.randomization_factor,
             multiplier: self.multiplier,
+            jitter: self.jitter,
             max_interval: self.max_interval,
             max_elapsed_time: self.max_elapsed_time,
-            clock: C::default(),
-            start_time: Instant::now(),
+            start_time: clock.now(),
+            clock,
+            rng: self.rng.clone(),
         }
     }
 }
@@ -246,6 +396,28 @@ fn get_randomized_interval() {
     assert_eq!(Duration::new(0, 3), f(0.5, 0.99, Duration::new(0, 2)));
 }
 
+#[test]
+fn get_jittered_interval() {
+    let f = ExponentialBackoff::<SystemClock>::get_jittered_interval;
+    let interval = Duration::from_secs(2);
+    // Symmetric jitter respects the randomization factor.
+    assert_eq!(
+        Duration::from_secs(1),
+        f(Jitter::Symmetric, 0.5, 0.0, interval)
+    );
+    // Full jitter ranges from zero to the interval.
+    assert_eq!(Duration::from_secs(0), f(Jitter::Full, 0.5, 0.0, interval));
+    assert_eq!(Duration::from_secs(1), f(Jitter::Full, 0.5, 0.5, interval));
+    // Equal jitter ranges from half of the interval to the interval.
+    assert_eq!(Duration::from_secs(1), f(Jitter::Equal, 0.5, 0.0, interval));
+    assert_eq!(
+        Duration::from_millis(1500),
+        f(Jitter::Equal, 0.5, 0.5, interval)
+    );
+    // No jitter returns the interval itself.
+    assert_eq!(interval, f(Jitter::None, 0.5, 0.99, interval));
+}
+
 #[test]
 fn exponential_backoff_builder() {
     let initial_interval = Duration::from_secs(1);
@@ -256,6 +428,7 @@ fn exponential_backoff_builder() {
         .with_initial_interval(initial_interval)
         .with_multiplier(multiplier)
         .with_randomization_factor(randomization_factor)
+        .with_jitter(Jitter::Full)
         .with_max_interval(max_interval)
         .with_max_elapsed_time(None)
         .build();
@@ -263,6 +436,7 @@ fn exponential_backoff_builder() {
     assert_eq!(backoff.current_interval, initial_interval);
     assert_eq!(backoff.multiplier, multiplier);
     assert_eq!(backoff.randomization_factor, randomization_factor);
+    assert_eq!(backoff.jitter, Jitter::Full);
     assert_eq!(backoff.max_interval, max_interval);
     assert_eq!(backoff.max_elapsed_time, None);
 }
@@ -280,6 +454,7 @@ fn exponential_backoff_default_builder() {
     );
     assert_eq!(backoff.multiplier, default::MULTIPLIER);
     assert_eq!(backoff.randomization_factor, default::RANDOMIZATION_FACTOR);
+    assert_eq!(backoff.jitter, Jitter::Symmetric);
     assert_eq!(
         backoff.max_interval,
         Duration::from_millis(default::MAX_INTERVAL_MILLIS)
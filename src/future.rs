@@ -1,17 +1,30 @@
 use std::{
     future::Future,
+    mem,
     pin::Pin,
+    sync::Arc,
     task::{Context, Poll},
     time::Duration,
 };
 
 use futures_core::ready;
+use instant::Instant;
 use pin_project_lite::pin_project;
 
-use crate::{backoff::Backoff, error::Error};
+use crate::{
+    backoff::Backoff,
+    classify::{Classify, Decision},
+    clock::{Clock, SystemClock},
+    error::{CancelError, DeadlineError, Error, RetryError, RetryResult, StopReason, TimeoutError},
+};
 
-use crate::retry::{NoopNotify, Notify};
+use crate::retry::{Deadline, NoopNotify, Notify, RetryAfterPolicy, RetryContext};
+use crate::span::{self, Span, Traced};
 
+/// Sleeper creates the futures [`Retry`] waits on between the attempts.
+///
+/// Implement it to use [`retry_with_sleeper`] and [`retry_notify_with_sleeper`] with
+/// runtimes other than tokio and async-std.
 pub trait Sleeper {
     type Sleep: Future<Output = ()> + Send + 'static;
     fn sleep(&self, dur: Duration) -> Self::Sleep;
@@ -23,6 +36,15 @@ pub trait Sleeper {
 ///
 /// Only available through the `tokio` and `async-std` feature flags.
 ///
+/// # Runtime selection
+///
+/// **If both the `tokio` and `async-std` features are enabled, the delays always use
+/// `TokioSleeper`, and this function is deprecated.** Enabling `tokio` anywhere in the
+/// dependency graph is enough, and awaiting the returned future outside of a tokio runtime
+/// then panics. Prefer `backoff::tokio::retry` or `backoff::async_std::retry`, which always
+/// use their runtime, over this function in libraries and in applications which may enable
+/// both features.
+///
 /// # Example
 ///
 /// ```rust
@@ -39,6 +61,15 @@ pub trait Sleeper {
 /// # fn main() { futures_executor::block_on(go()); }
 /// ```
 #[cfg(any(feature = "tokio", feature = "async-std"))]
+#[cfg_attr(
+    all(feature = "tokio", feature = "async-std"),
+    deprecated(
+        note = "both the tokio and async-std features are enabled, so this silently uses tokio; \
+                use the backoff::tokio or backoff::async_std module instead"
+    )
+)]
+// Calls the entry points deprecated along with it.
+#[allow(deprecated)]
 pub fn retry<I, E, Fn, Fut, B>(
     backoff: B,
     operation: Fn,
@@ -58,15 +89,25 @@ where
 ///
 /// Only available through the `tokio` and `async-std` feature flags.
 ///
+/// # Runtime selection
+///
+/// **If both the `tokio` and `async-std` features are enabled, the delays always use
+/// `TokioSleeper`, and this function is deprecated.** Enabling `tokio` anywhere in the
+/// dependency graph is enough, and awaiting the returned future outside of a tokio runtime
+/// then panics. Prefer `backoff::tokio::retry_notify` or `backoff::async_std::retry_notify`,
+/// which always use their runtime, over this function in libraries and in applications which
+/// may enable both features.
+///
 /// # Async `notify`
 ///
 /// `notify` can be neither `async fn` or [`Future`]. If you need to perform some async
-/// operations inside `notify`, consider using your runtimes task-spawning functionality.
+/// operations when an attempt fails, use [`Retry::with_async_notify`] instead: [`Retry`] polls
+/// the future returned by [`AsyncNotify`] to completion before the delay starts, so
+/// notifications keep their order.
 ///
-/// The reason behind this is that [`Retry`] future cannot be responsible for polling
-/// `notify` future, because can easily be dropped _before_ `notify` is completed.
-/// So, considering the fact that most of the time no async operations are required in
-/// `notify`, it's up to the caller to decide how async `notify` should be performed.
+/// Keep in mind that [`Retry`] owns that future, and can easily be dropped _before_ it is
+/// completed, e.g. by a timeout or `select!`. Use your runtimes task-spawning functionality
+/// for notifications which must complete regardless.
 ///
 /// # Example
 ///
@@ -90,8 +131,15 @@ where
 /// # fn main() { futures_executor::block_on(go()); }
 /// ```
 #[cfg(any(feature = "tokio", feature = "async-std"))]
+#[cfg_attr(
+    all(feature = "tokio", feature = "async-std"),
+    deprecated(
+        note = "both the tokio and async-std features are enabled, so this silently uses tokio; \
+                use the backoff::tokio or backoff::async_std module instead"
+    )
+)]
 pub fn retry_notify<I, E, Fn, Fut, B, N>(
-    mut backoff: B,
+    backoff: B,
     operation: Fn,
     notify: N,
 ) -> Retry<impl Sleeper, B, N, Fn, Fut>
@@ -100,14 +148,326 @@ where
     Fn: FnMut() -> Fut,
     Fut: Future<Output = Result<I, Error<E>>>,
     N: Notify<E>,
+{
+    #[cfg(feature = "tokio")]
+    return crate::tokio::retry_notify(backoff, operation, notify);
+
+    #[cfg(not(feature = "tokio"))]
+    return crate::async_std::retry_notify(backoff, operation, notify);
+}
+
+/// Retries given `operation` according to the [`Backoff`] policy, with `classify` deciding
+/// which of its errors are retried. Unlike [`retry`], the operation returns plain errors.
+/// [`Backoff`] is reset before it is used.
+///
+/// Only available through the `tokio` and `async-std` feature flags, see [`classified`] to use
+/// a classifier with the other entry points.
+///
+/// # Example
+///
+/// ```rust
+/// use backoff::classify::Decision;
+/// use backoff::ExponentialBackoff;
+///
+/// async fn f() -> Result<(), &'static str> {
+///     // Business logic...
+///     Err("not found")
+/// }
+///
+/// # async fn go() {
+/// let classify = |err: &&str| match *err {
+///     "not found" => Decision::Permanent,
+///     _ => Decision::Transient,
+/// };
+/// let err = backoff::future::retry_if(ExponentialBackoff::default(), f, classify)
+///     .await
+///     .err()
+///     .unwrap();
+/// assert_eq!(err, "not found");
+/// # }
+/// # fn main() { futures_executor::block_on(go()); }
+/// ```
+#[cfg(any(feature = "tokio", feature = "async-std"))]
+#[cfg_attr(
+    all(feature = "tokio", feature = "async-std"),
+    deprecated(
+        note = "both the tokio and async-std features are enabled, so this silently uses tokio; \
+                use the backoff::tokio or backoff::async_std module instead"
+    )
+)]
+// Calls the entry points deprecated along with it.
+#[allow(deprecated)]
+pub fn retry_if<I, E, Fn, Fut, B, C>(
+    backoff: B,
+    operation: Fn,
+    classify: C,
+) -> Retry<impl Sleeper, B, NoopNotify, impl FnMut() -> Classified<Fut, C>, Classified<Fut, C>>
+where
+    B: Backoff,
+    Fn: FnMut() -> Fut,
+    Fut: Future<Output = Result<I, E>>,
+    C: Classify<E>,
+{
+    retry(backoff, classified(classify, operation))
+}
+
+/// Wraps `operation`, which returns plain errors, so `classify` decides which of them are
+/// retried.
+///
+/// # Example
+///
+/// ```rust
+/// use backoff::backoff::{BackoffExt, Zero};
+/// use backoff::classify::Decision;
+/// use backoff::future::{classified, retry_with_sleeper};
+/// # #[cfg(feature = "testing")]
+/// use backoff::testing::{MockClock, MockSleeper};
+///
+/// async fn f() -> Result<(), &'static str> {
+///     Err("unavailable")
+/// }
+///
+/// # #[cfg(feature = "testing")]
+/// # async fn go() {
+/// let sleeper = MockSleeper::new(MockClock::new());
+/// let operation = classified(|_: &&str| Decision::Transient, f);
+/// let err = retry_with_sleeper(sleeper, Zero {}.with_max_retries(2), operation)
+///     .await
+///     .err()
+///     .unwrap();
+/// assert_eq!(err, "unavailable");
+/// # }
+/// # #[cfg(feature = "testing")]
+/// # fn main() { futures_executor::block_on(go()); }
+/// # #[cfg(not(feature = "testing"))]
+/// # fn main() {}
+/// ```
+pub fn classified<C, Fn, Fut, I, E>(
+    classify: C,
+    mut operation: Fn,
+) -> impl FnMut() -> Classified<Fut, C>
+where
+    C: Classify<E>,
+    Fn: FnMut() -> Fut,
+    Fut: Future<Output = Result<I, E>>,
+{
+    let classify = Arc::new(classify);
+    move || Classified {
+        fut: operation(),
+        classify: classify.clone(),
+    }
+}
+
+pin_project! {
+    /// A single attempt whose error is classified, see [`classified`].
+    pub struct Classified<Fut, C> {
+        // The attempt.
+        #[pin]
+        fut: Fut,
+
+        // The [`Classify`] implementation, shared by the attempts.
+        classify: Arc<C>,
+    }
+}
+
+impl<Fut, C, I, E> Future for Classified<Fut, C>
+where
+    C: Classify<E>,
+    Fut: Future<Output = Result<I, E>>,
+{
+    type Output = Result<I, Error<E>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let classify = this.classify;
+        let res = ready!(this.fut.poll(cx));
+        Poll::Ready(res.map_err(|err| classify.error(err)))
+    }
+}
+
+/// Retries given `operation` according to the [`Backoff`] policy, calling `notify` on failed
+/// attempts. Before each retry, the async `classify` decides whether the transient error is
+/// retried and after which delay, see [`AsyncClassify`].
+/// [`Backoff`] is reset before it is used.
+///
+/// Only available through the `tokio` and `async-std` feature flags, see
+/// [`Retry::with_async_classifier`] to use an async classifier with the other entry points.
+///
+/// # Example
+///
+/// ```rust
+/// use backoff::classify::Decision;
+/// use backoff::ExponentialBackoff;
+///
+/// async fn f() -> Result<(), backoff::Error<&'static str>> {
+///     // Business logic...
+///     Err(backoff::Error::transient("lease expired"))
+/// }
+///
+/// async fn has_lease() -> bool {
+///     // E.g. asking a lock service...
+///     false
+/// }
+///
+/// # async fn go() {
+/// let classify = |_: &&str| async {
+///     if has_lease().await {
+///         Decision::Transient
+///     } else {
+///         Decision::Permanent
+///     }
+/// };
+/// let err = backoff::future::retry_notify_with_classifier(
+///     ExponentialBackoff::default(),
+///     f,
+///     |e, dur| println!("Error happened at {:?}: {}", dur, e),
+///     classify,
+/// )
+/// .await
+/// .err()
+/// .unwrap();
+/// assert_eq!(err, "lease expired");
+/// # }
+/// # fn main() { futures_executor::block_on(go()); }
+/// ```
+#[cfg(any(feature = "tokio", feature = "async-std"))]
+#[cfg_attr(
+    all(feature = "tokio", feature = "async-std"),
+    deprecated(
+        note = "both the tokio and async-std features are enabled, so this silently uses tokio; \
+                use the backoff::tokio or backoff::async_std module instead"
+    )
+)]
+#[allow(clippy::type_complexity, deprecated)]
+pub fn retry_notify_with_classifier<I, E, Fn, Fut, B, N, C>(
+    backoff: B,
+    operation: Fn,
+    notify: N,
+    classify: C,
+) -> Retry<impl Sleeper, B, N, Fn, Fut, SystemClock, AsyncClassifier<C, C::Future, ()>>
+where
+    B: Backoff,
+    Fn: FnMut() -> Fut,
+    Fut: Future<Output = Result<I, Error<E>>>,
+    N: Notify<E>,
+    C: AsyncClassify<E>,
+{
+    retry_notify(backoff, operation, notify).with_async_classifier(classify)
+}
+
+/// AsyncClassify decides whether a transient error of an operation is retried, with async
+/// work like checking a lease or refreshing credentials, see [`Retry::with_async_classifier`].
+///
+/// [`Retry`] polls the returned future to completion after the failed attempt, and before the
+/// delay starts:
+/// - [`Decision::Permanent`] turns the error permanent, so it's returned immediately.
+/// - [`Decision::Transient`] keeps the error as the operation returned it.
+/// - [`Decision::RetryAfter`] retries after the given duration instead of the one of the
+///   [`Backoff`] policy.
+///
+/// Permanent errors of the operation are returned without calling it. It's implemented for
+/// closures `Fn(&E) -> impl Future<Output = Decision>`; the returned future can't borrow the
+/// error, clone the parts it needs.
+///
+/// [`Decision::Permanent`]: crate::classify::Decision::Permanent
+/// [`Decision::Transient`]: crate::classify::Decision::Transient
+/// [`Decision::RetryAfter`]: crate::classify::Decision::RetryAfter
+pub trait AsyncClassify<E> {
+    type Future: Future<Output = Decision>;
+
+    fn classify(&self, err: &E) -> Self::Future;
+}
+
+impl<E, F, Fut> AsyncClassify<E> for F
+where
+    F: Fn(&E) -> Fut,
+    Fut: Future<Output = Decision>,
+{
+    type Future = Fut;
+
+    fn classify(&self, err: &E) -> Self::Future {
+        self(err)
+    }
+}
+
+/// Retries given `operation` according to the [`Backoff`] policy, waiting between the
+/// attempts on the futures created by `sleeper`.
+/// [`Backoff`] is reset before it is used.
+///
+/// Unlike `retry`, it doesn't depend on a specific runtime.
+///
+/// # Example
+///
+/// ```rust
+/// use backoff::backoff::{BackoffExt, Constant};
+/// use backoff::future::Sleeper;
+/// use std::future::{ready, Ready};
+/// use std::time::Duration;
+///
+/// struct NoSleep;
+///
+/// impl Sleeper for NoSleep {
+///     type Sleep = Ready<()>;
+///     fn sleep(&self, _: Duration) -> Self::Sleep {
+///         ready(())
+///     }
+/// }
+///
+/// async fn f() -> Result<(), backoff::Error<&'static str>> {
+///     // Business logic...
+///     Err(backoff::Error::transient("error"))
+/// }
+///
+/// # async fn go() {
+/// let backoff = Constant::new(Duration::from_secs(1)).with_max_retries(3);
+/// let err = backoff::future::retry_with_sleeper(NoSleep, backoff, f)
+///     .await
+///     .err()
+///     .unwrap();
+/// assert_eq!(err, "error");
+/// # }
+/// # fn main() { futures_executor::block_on(go()); }
+/// ```
+pub fn retry_with_sleeper<S, I, E, Fn, Fut, B>(
+    sleeper: S,
+    backoff: B,
+    operation: Fn,
+) -> Retry<S, B, NoopNotify, Fn, Fut>
+where
+    S: Sleeper,
+    B: Backoff,
+    Fn: FnMut() -> Fut,
+    Fut: Future<Output = Result<I, Error<E>>>,
+{
+    retry_notify_with_sleeper(sleeper, backoff, operation, NoopNotify)
+}
+
+/// Retries given `operation` according to the [`Backoff`] policy, waiting between the
+/// attempts on the futures created by `sleeper`.
+/// Calls `notify` on failed attempts (in case of [`Error::Transient`]).
+/// [`Backoff`] is reset before it is used.
+///
+/// Unlike `retry_notify`, it doesn't depend on a specific runtime.
+pub fn retry_notify_with_sleeper<S, I, E, Fn, Fut, B, N>(
+    sleeper: S,
+    mut backoff: B,
+    operation: Fn,
+    notify: N,
+) -> Retry<S, B, N, Fn, Fut>
+where
+    S: Sleeper,
+    B: Backoff,
+    Fn: FnMut() -> Fut,
+    Fut: Future<Output = Result<I, Error<E>>>,
+    N: Notify<E>,
 {
     backoff.reset();
-    Retry::new(rt_sleeper(), backoff, notify, operation)
+    Retry::new(sleeper, backoff, notify, operation)
 }
 
 pin_project! {
     /// Retry implementation.
-    pub struct Retry<S: Sleeper, B, N, Fn, Fut> {
+    pub struct Retry<S: Sleeper, B, N, Fn, Fut: Future, C = SystemClock, H = ()> {
         // The [`Sleeper`] that we generate the `delay` futures from.
         sleeper: S,
 
@@ -118,6 +478,9 @@ pin_project! {
         #[pin]
         delay: OptionPinned<S::Sleep>,
 
+        // The duration of the next `delay`, until the pending notification completes.
+        pending_delay: Option<Duration>,
+
         // Operation to be retried. It must return [`Future`].
         operation: Fn,
 
@@ -127,6 +490,39 @@ pin_project! {
 
         // [`Notify`] implementation to track [`Retry`] ticks.
         notify: N,
+
+        // The async hooks, see [`Retry::with_async_notify`] and [`Retry::with_async_classifier`].
+        hooks: H,
+
+        // The result of the last attempt and the time it completed at, while `hooks` decide on
+        // its transient error.
+        undecided: Option<(Fut::Output, Instant)>,
+
+        // The number of the current attempt, starting from 1.
+        attempt: u32,
+
+        // The time the first attempt was polled at.
+        start: Option<Instant>,
+
+        // The time the current attempt was first polled at.
+        attempt_start: Option<Instant>,
+
+        // [`Clock`] to measure the elapsed time with.
+        clock: C,
+
+        // The deadline of the loop, see [`Deadlined`].
+        deadline: Option<Deadline>,
+
+        // [`Future`] which completes at the deadline, created once an attempt is pending.
+        #[pin]
+        deadline_sleep: OptionPinned<S::Sleep>,
+
+        // [`RetryAfterPolicy`] deciding how the `retry_after` durations are used.
+        retry_after: RetryAfterPolicy,
+
+        // The span of the loop, and the one of the current attempt.
+        span: Span,
+        attempt_span: Span,
     }
 }
 
@@ -137,14 +533,279 @@ where
     Fut: Future<Output = Result<I, Error<E>>>,
 {
     pub fn new(sleeper: S, backoff: B, notify: N, mut operation: Fn) -> Self {
-        let fut = operation();
+        let span = Span::retry();
+        let attempt_span = span.attempt(1);
+        let fut = {
+            let _attempt = attempt_span.enter();
+            operation()
+        };
         Retry {
             sleeper,
             backoff,
             delay: OptionPinned::None,
+            pending_delay: None,
             operation,
             fut,
             notify,
+            hooks: (),
+            undecided: None,
+            attempt: 1,
+            start: None,
+            attempt_start: None,
+            clock: SystemClock::default(),
+            deadline: None,
+            deadline_sleep: OptionPinned::None,
+            retry_after: RetryAfterPolicy::default(),
+            span,
+            attempt_span,
+        }
+    }
+}
+
+impl<S, B, N, Fn, Fut, C, H> Retry<S, B, N, Fn, Fut, C, H>
+where
+    S: Sleeper,
+    Fut: Future,
+{
+    /// Measures the elapsed time with `clock`.
+    pub fn with_clock<D: Clock>(self, clock: D) -> Retry<S, B, N, Fn, Fut, D, H> {
+        Retry {
+            sleeper: self.sleeper,
+            backoff: self.backoff,
+            delay: self.delay,
+            pending_delay: self.pending_delay,
+            operation: self.operation,
+            fut: self.fut,
+            notify: self.notify,
+            hooks: self.hooks,
+            undecided: self.undecided,
+            attempt: self.attempt,
+            start: self.start,
+            attempt_start: self.attempt_start,
+            clock,
+            deadline: self.deadline,
+            deadline_sleep: self.deadline_sleep,
+            retry_after: self.retry_after,
+            span: self.span,
+            attempt_span: self.attempt_span,
+        }
+    }
+
+    /// Uses the `retry_after` durations of the transient errors according to `policy`.
+    pub fn with_retry_after(mut self, policy: RetryAfterPolicy) -> Self {
+        self.retry_after = policy;
+        self
+    }
+
+    /// Calls `notify` on failed attempts, and waits for the future it returns before the delay
+    /// starts, see [`AsyncNotify`].
+    ///
+    /// It's called in addition to the [`Notify`] of the loop. Calling it again adds another
+    /// async `notify`, their futures are awaited in order. The time they take counts toward the
+    /// deadline, which gives up on them once it passes.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use backoff::backoff::{BackoffExt, Zero};
+    /// use backoff::future::retry_with_sleeper;
+    /// # #[cfg(feature = "testing")]
+    /// use backoff::testing::{MockClock, MockSleeper};
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// async fn f() -> Result<(), backoff::Error<&'static str>> {
+    ///     Err(backoff::Error::transient("error"))
+    /// }
+    ///
+    /// # #[cfg(feature = "testing")]
+    /// # async fn go() {
+    /// let sleeper = MockSleeper::new(MockClock::new());
+    /// let audit_log = Arc::new(Mutex::new(Vec::new()));
+    /// let log = audit_log.clone();
+    /// let notify = move |err: &&'static str, _| {
+    ///     let (log, err) = (log.clone(), *err);
+    ///     // E.g. writing to a database...
+    ///     async move { log.lock().unwrap().push(err) }
+    /// };
+    ///
+    /// let _ = retry_with_sleeper(sleeper, Zero {}.with_max_retries(2), f)
+    ///     .with_async_notify(notify)
+    ///     .await;
+    /// assert_eq!(*audit_log.lock().unwrap(), vec!["error"; 2]);
+    /// # }
+    /// # #[cfg(feature = "testing")]
+    /// # fn main() { futures_executor::block_on(go()); }
+    /// # #[cfg(not(feature = "testing"))]
+    /// # fn main() {}
+    /// ```
+    #[allow(clippy::type_complexity)]
+    pub fn with_async_notify<A, E>(
+        self,
+        notify: A,
+    ) -> Retry<S, B, N, Fn, Fut, C, AsyncNotifier<A, A::Future, H>>
+    where
+        A: AsyncNotify<E>,
+    {
+        self.map(
+            |n| n,
+            |hooks| AsyncNotifier {
+                notify,
+                pending: None,
+                hooks,
+            },
+        )
+    }
+
+    /// Calls the async `classify` on the transient errors, deciding whether they are retried
+    /// and after which delay, see [`AsyncClassify`].
+    ///
+    /// It's polled after the failed attempt and before the delay, the time it takes doesn't
+    /// count toward the attempt. It counts toward the deadline though: the loop gives up once
+    /// the deadline passes while deciding. Calling it again adds another async `classify`,
+    /// called only if the previous ones decide on [`Decision::Transient`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use backoff::backoff::{BackoffExt, Zero};
+    /// use backoff::classify::Decision;
+    /// use backoff::future::retry_with_sleeper;
+    /// # #[cfg(feature = "testing")]
+    /// use backoff::testing::{MockClock, MockSleeper};
+    /// use std::time::Duration;
+    ///
+    /// async fn f() -> Result<(), backoff::Error<&'static str>> {
+    ///     // Business logic...
+    ///     Err(backoff::Error::transient("unauthorized"))
+    /// }
+    ///
+    /// async fn refresh_credentials() -> Result<(), ()> {
+    ///     // E.g. asking an identity provider...
+    ///     Err(())
+    /// }
+    ///
+    /// # #[cfg(feature = "testing")]
+    /// # async fn go() {
+    /// let sleeper = MockSleeper::new(MockClock::new());
+    /// let classify = |_: &&str| async {
+    ///     match refresh_credentials().await {
+    ///         Ok(()) => Decision::RetryAfter(Duration::ZERO),
+    ///         Err(_) => Decision::Permanent,
+    ///     }
+    /// };
+    /// let err = retry_with_sleeper(sleeper, Zero {}.with_max_retries(2), f)
+    ///     .with_async_classifier(classify)
+    ///     .await
+    ///     .err()
+    ///     .unwrap();
+    /// assert_eq!(err, "unauthorized");
+    /// # }
+    /// # #[cfg(feature = "testing")]
+    /// # fn main() { futures_executor::block_on(go()); }
+    /// # #[cfg(not(feature = "testing"))]
+    /// # fn main() {}
+    /// ```
+    #[allow(clippy::type_complexity)]
+    pub fn with_async_classifier<A, E>(
+        self,
+        classify: A,
+    ) -> Retry<S, B, N, Fn, Fut, C, AsyncClassifier<A, A::Future, H>>
+    where
+        A: AsyncClassify<E>,
+    {
+        self.map(
+            |n| n,
+            |hooks| AsyncClassifier {
+                classify,
+                pending: None,
+                hooks,
+            },
+        )
+    }
+
+    /// Gives up once `deadline` is reached, whatever the backoff policy is.
+    ///
+    /// If the next delay would reach the deadline, the loop gives up right away with the error
+    /// of the last attempt. An attempt still running at the deadline is cancelled. Either way,
+    /// the loop fails with [`DeadlineError::Exceeded`], else with [`DeadlineError::Failed`].
+    /// Giving up without an error to report calls [`Notify::on_deadline`] instead of
+    /// [`Notify::on_give_up`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "tokio")]
+    /// # {
+    /// use backoff::backoff::Constant;
+    /// use backoff::future::retry;
+    /// use backoff::DeadlineError;
+    /// use std::time::Duration;
+    ///
+    /// async fn f() -> Result<(), backoff::Error<&'static str>> {
+    ///     // An attempt which hangs...
+    ///     std::future::pending().await
+    /// }
+    ///
+    /// # async fn go() {
+    /// let err = retry(Constant::new(Duration::from_millis(10)), f)
+    ///     .with_deadline(Duration::from_millis(10))
+    ///     .await
+    ///     .err()
+    ///     .unwrap();
+    /// assert_eq!(err, DeadlineError::Exceeded(None));
+    /// # }
+    /// # tokio_1::runtime::Runtime::new().unwrap().block_on(go());
+    /// # }
+    /// ```
+    pub fn with_deadline<D: Into<Deadline>>(
+        mut self,
+        deadline: D,
+    ) -> Deadlined<S, B, N, Fn, Fut, C, H> {
+        self.deadline = Some(deadline.into());
+        Deadlined { retry: self }
+    }
+
+    // Wraps `notify` into a [`Recorder`].
+    fn record<E>(
+        self,
+        clone: Option<fn(&E) -> E>,
+        history: bool,
+    ) -> Retry<S, B, Recorder<N, E>, Fn, Fut, C, H> {
+        self.map(
+            |notify| Recorder {
+                notify,
+                clone,
+                history,
+                errors: Vec::new(),
+            },
+            |hooks| hooks,
+        )
+    }
+
+    fn map<M, K>(
+        self,
+        notify: impl FnOnce(N) -> M,
+        hooks: impl FnOnce(H) -> K,
+    ) -> Retry<S, B, M, Fn, Fut, C, K> {
+        Retry {
+            sleeper: self.sleeper,
+            backoff: self.backoff,
+            delay: self.delay,
+            pending_delay: self.pending_delay,
+            operation: self.operation,
+            fut: self.fut,
+            notify: notify(self.notify),
+            hooks: hooks(self.hooks),
+            undecided: self.undecided,
+            attempt: self.attempt,
+            start: self.start,
+            attempt_start: self.attempt_start,
+            clock: self.clock,
+            deadline: self.deadline,
+            deadline_sleep: self.deadline_sleep,
+            retry_after: self.retry_after,
+            span: self.span,
+            attempt_span: self.attempt_span,
         }
     }
 }
@@ -160,80 +821,766 @@ pin_project! {
     }
 }
 
-impl<S, B, N, Fn, Fut, I, E> Future for Retry<S, B, N, Fn, Fut>
+impl<S, B, N, Fn, Fut, C, H, I, E> Future for Retry<S, B, N, Fn, Fut, C, H>
 where
     S: Sleeper,
     B: Backoff,
+    C: Clock,
     N: Notify<E>,
+    H: Hooks<E>,
     Fn: FnMut() -> Fut,
     Fut: Future<Output = Result<I, Error<E>>>,
+    E: Traced,
 {
     type Output = Result<I, E>;
 
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.poll_report(cx)
+            .map(|res| res.map_err(RetryError::into_inner))
+    }
+}
+
+impl<S, B, N, Fn, Fut, C, H, I, E> Retry<S, B, N, Fn, Fut, C, H>
+where
+    S: Sleeper,
+    B: Backoff,
+    C: Clock,
+    N: Notify<E>,
+    H: Hooks<E>,
+    Fn: FnMut() -> Fut,
+    Fut: Future<Output = Result<I, Error<E>>>,
+    E: Traced,
+{
+    // Drives the loop, resolving to the report of why it gave up on failure.
+    fn poll_report(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<RetryResult<I, E>> {
+        // Only a [`Deadlined`] loop has a deadline cancelling the attempts.
+        self.poll_loop(cx)
+            .map(|res| res.map_err(|report| report.expect("no deadline")))
+    }
+
+    // Drives the loop, resolving to the report of why it gave up on failure, or to `None` if
+    // the deadline passed while there was no error to report: during an attempt, or after
+    // notifying the failure of the previous one.
+    fn poll_loop(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<I, Option<RetryError<E>>>> {
         let mut this = self.project();
+        let _retry = this.span.enter();
+        let (sleeper, clock) = (&*this.sleeper, &*this.clock);
+        let start = *this.start.get_or_insert_with(|| clock.now());
+        let deadline = this.deadline.and_then(|deadline| deadline.instant(start));
+
+        // The number of attempts started when the deadline passed.
+        let attempts = loop {
+            if let Some(duration) = *this.pending_delay {
+                if this.hooks.poll_notified(cx).is_pending() {
+                    let deadline_sleep = this.deadline_sleep.as_mut();
+                    ready!(poll_deadline(deadline_sleep, sleeper, clock, deadline, cx));
+                    // The attempt count was already advanced to the next attempt.
+                    break *this.attempt - 1;
+                }
+                *this.pending_delay = None;
+                this.delay.set(OptionPinned::Some {
+                    inner: sleeper.sleep(duration),
+                });
+            }
 
-        loop {
             if let OptionProj::Some { inner: delay } = this.delay.as_mut().project() {
-                ready!(delay.poll(cx));
+                if delay.poll(cx).is_pending() {
+                    let deadline_sleep = this.deadline_sleep.as_mut();
+                    ready!(poll_deadline(deadline_sleep, sleeper, clock, deadline, cx));
+                    break *this.attempt - 1;
+                }
                 this.delay.set(OptionPinned::None);
             }
 
-            match ready!(this.fut.as_mut().poll(cx)) {
-                Ok(v) => return Poll::Ready(Ok(v)),
-                Err(Error::Permanent(e)) => return Poll::Ready(Err(e)),
+            let _attempt = this.attempt_span.enter();
+            let attempt_start = *this.attempt_start.get_or_insert_with(|| clock.now());
+
+            // The result of the attempt, and when it completed.
+            let (res, now) = match this.undecided.take() {
+                Some(undecided) => undecided,
+                None => match this.fut.as_mut().poll(cx) {
+                    Poll::Ready(res) => (res, clock.now()),
+                    Poll::Pending => {
+                        let deadline_sleep = this.deadline_sleep.as_mut();
+                        ready!(poll_deadline(deadline_sleep, sleeper, clock, deadline, cx));
+                        break *this.attempt;
+                    }
+                },
+            };
+
+            // Whether the deadline passed while deciding on a transient error.
+            let mut expired = false;
+            let res = match res {
                 Err(Error::Transient { err, retry_after }) => {
-                    match retry_after.or_else(|| this.backoff.next_backoff()) {
-                        Some(duration) => {
-                            this.notify.notify(err, duration);
-                            this.delay.set(OptionPinned::Some {
-                                inner: this.sleeper.sleep(duration),
-                            });
-                            this.fut.set((this.operation)());
+                    match this.hooks.poll_decision(&err, cx) {
+                        Poll::Ready(Decision::Transient) => {
+                            Err(Error::Transient { err, retry_after })
+                        }
+                        Poll::Ready(decision) => Err(decision.apply(err)),
+                        Poll::Pending => {
+                            let res = Err(Error::Transient { err, retry_after });
+                            let deadline_sleep = this.deadline_sleep.as_mut();
+                            if poll_deadline(deadline_sleep, sleeper, clock, deadline, cx)
+                                .is_pending()
+                            {
+                                *this.undecided = Some((res, now));
+                                return Poll::Pending;
+                            }
+                            expired = true;
+                            res
                         }
-                        None => return Poll::Ready(Err(err)),
                     }
                 }
+                res => res,
+            };
+
+            let (err, next) = match res {
+                Ok(v) => {
+                    let elapsed = now.duration_since(start);
+                    span::succeeded(*this.attempt, elapsed);
+                    this.notify.on_success(*this.attempt, elapsed);
+                    return Poll::Ready(Ok(v));
+                }
+                Err(Error::Permanent(err)) => (err, Err(StopReason::Permanent)),
+                Err(Error::Transient { err, .. }) if expired => (err, Err(StopReason::Deadline)),
+                Err(Error::Transient { err, retry_after }) => {
+                    match this.retry_after.next(retry_after, this.backoff) {
+                        Some(duration) => (err, Ok(duration)),
+                        None => (err, Err(StopReason::Exhausted)),
+                    }
+                }
+            };
+
+            let next = match (next, deadline) {
+                // No time is left for another attempt, counting the time spent deciding.
+                (Ok(duration), Some(deadline))
+                    if deadline.saturating_duration_since(clock.now()) <= duration =>
+                {
+                    Err(StopReason::Deadline)
+                }
+                (next, _) => next,
+            };
+            match next {
+                Ok(duration) => {
+                    let context = RetryContext {
+                        attempt: *this.attempt,
+                        elapsed: now.duration_since(start),
+                        attempt_duration: now.duration_since(attempt_start),
+                    };
+                    span::failed(&err, duration, *this.attempt);
+                    this.hooks.notify(&err, duration, &context);
+                    this.notify.notify_with_context(err, duration, &context);
+                    *this.attempt += 1;
+                    *this.attempt_start = None;
+                    *this.pending_delay = Some(duration);
+                    *this.attempt_span = this.span.attempt(*this.attempt);
+                    let _next = this.attempt_span.enter();
+                    this.fut.set((this.operation)());
+                }
+                Err(reason) => {
+                    let elapsed = clock.now().duration_since(start);
+                    let report = RetryError::new(err, reason, *this.attempt, elapsed);
+                    span::gave_up(&report);
+                    this.notify.on_give_up(&report);
+                    return Poll::Ready(Err(Some(report)));
+                }
             }
+        };
+
+        // The deadline passed with no error to report.
+        let elapsed = clock.now().duration_since(start);
+        span::deadline(attempts, elapsed);
+        this.notify.on_deadline(attempts, elapsed);
+        Poll::Ready(Err(None))
+    }
+}
+
+// Polls `sleep` until `deadline`, creating it on the first call. It's never ready without a
+// deadline.
+fn poll_deadline<S: Sleeper, C: Clock>(
+    mut sleep: Pin<&mut OptionPinned<S::Sleep>>,
+    sleeper: &S,
+    clock: &C,
+    deadline: Option<Instant>,
+    cx: &mut Context<'_>,
+) -> Poll<()> {
+    let deadline = match deadline {
+        Some(deadline) => deadline,
+        None => return Poll::Pending,
+    };
+    if let OptionProj::None = sleep.as_mut().project() {
+        let remaining = deadline.saturating_duration_since(clock.now());
+        sleep.set(OptionPinned::Some {
+            inner: sleeper.sleep(remaining),
+        });
+    }
+    match sleep.project() {
+        OptionProj::Some { inner } => inner.poll(cx),
+        OptionProj::None => unreachable!(),
+    }
+}
+
+impl<S, B, N, Fn, Fut, C, H, I, E> Retry<S, B, N, Fn, Fut, C, H>
+where
+    S: Sleeper,
+    Fut: Future<Output = Result<I, Error<E>>>,
+    E: Clone,
+{
+    /// Stops the loop before the next attempt once `cancel` completes, e.g. on shutdown.
+    ///
+    /// If `cancel` completes while waiting for the next attempt, the wait is cut short and the
+    /// loop fails with [`CancelError::Cancelled`], holding the error of the last attempt. A
+    /// running attempt isn't interrupted: if it fails, the loop is cancelled before waiting.
+    /// Otherwise it fails with [`CancelError::Failed`], the same way as without `cancel`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "tokio")]
+    /// # {
+    /// use backoff::backoff::Constant;
+    /// use backoff::future::retry;
+    /// use backoff::CancelError;
+    /// use std::time::Duration;
+    ///
+    /// async fn f() -> Result<(), backoff::Error<&'static str>> {
+    ///     Err(backoff::Error::transient("error"))
+    /// }
+    ///
+    /// # async fn go() {
+    /// let shutdown = tokio_1::time::sleep(Duration::from_millis(10));
+    /// let err = retry(Constant::new(Duration::from_secs(60)), f)
+    ///     .with_cancel(shutdown)
+    ///     .await
+    ///     .err()
+    ///     .unwrap();
+    /// assert_eq!(err, CancelError::Cancelled("error"));
+    /// # }
+    /// # tokio_1::runtime::Runtime::new().unwrap().block_on(go());
+    /// # }
+    /// ```
+    pub fn with_cancel<X>(self, cancel: X) -> Cancellable<S, B, N, Fn, Fut, C, X, E, H>
+    where
+        X: Future<Output = ()>,
+    {
+        Cancellable {
+            retry: self.record(Some(E::clone), false),
+            cancel,
+            cancelled: false,
+        }
+    }
+
+    /// Same as [`report`](#method.report), with the errors of the attempts before the last one
+    /// recorded in [`RetryError::history`].
+    pub fn report_with_history(self) -> Report<S, B, N, Fn, Fut, C, E, H> {
+        Report {
+            retry: self.record(Some(E::clone), true),
+        }
+    }
+}
+
+impl<S, B, N, Fn, Fut, C, H, I, E> Retry<S, B, N, Fn, Fut, C, H>
+where
+    S: Sleeper,
+    Fut: Future<Output = Result<I, Error<E>>>,
+{
+    /// Resolves to a [`RetryError`] on failure instead of the bare error, reporting how many
+    /// attempts ran, how long they took and why the loop gave up.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use backoff::backoff::{BackoffExt, Zero};
+    /// use backoff::future::retry_with_sleeper;
+    /// # #[cfg(feature = "testing")]
+    /// use backoff::testing::{MockClock, MockSleeper};
+    /// use backoff::StopReason;
+    ///
+    /// async fn f() -> Result<(), backoff::Error<&'static str>> {
+    ///     Err(backoff::Error::transient("error"))
+    /// }
+    ///
+    /// # #[cfg(feature = "testing")]
+    /// # async fn go() {
+    /// let sleeper = MockSleeper::new(MockClock::new());
+    /// let backoff = Zero {}.with_max_retries(2);
+    /// let report = retry_with_sleeper(sleeper, backoff, f)
+    ///     .report()
+    ///     .await
+    ///     .unwrap_err();
+    /// assert_eq!(report.err, "error");
+    /// assert_eq!(report.reason, StopReason::Exhausted);
+    /// assert_eq!(report.attempts, 3);
+    /// # }
+    /// # #[cfg(feature = "testing")]
+    /// # fn main() { futures_executor::block_on(go()); }
+    /// # #[cfg(not(feature = "testing"))]
+    /// # fn main() {}
+    /// ```
+    pub fn report(self) -> Report<S, B, N, Fn, Fut, C, E, H> {
+        Report {
+            retry: self.record(None, false),
         }
     }
 }
 
-#[cfg(all(feature = "tokio", feature = "async-std"))]
-compile_error!("Feature \"tokio\" and \"async-std\" cannot be enabled at the same time");
+impl<S, B, N, Fn, Fut, C, H, E> Retry<S, B, Recorder<N, E>, Fn, Fut, C, H>
+where
+    S: Sleeper,
+    N: Notify<E>,
+    Fut: Future,
+    C: Clock,
+    E: Traced,
+{
+    // Takes the error of the last attempt if the loop is waiting for the next one, giving up.
+    fn take_last_error(self: Pin<&mut Self>) -> Option<E> {
+        let this = self.project();
+        let err = match this.delay.project() {
+            OptionProj::Some { .. } => this.notify.errors.pop()?,
+            OptionProj::None if this.pending_delay.is_some() => this.notify.errors.pop()?,
+            OptionProj::None => return None,
+        };
 
-#[cfg(feature = "async-std")]
-fn rt_sleeper() -> impl Sleeper {
-    AsyncStdSleeper
+        // The attempt count was already advanced to the next attempt.
+        let attempts = *this.attempt - 1;
+        let clock = &*this.clock;
+        let elapsed = this
+            .start
+            .map_or(Duration::ZERO, |start| clock.now().duration_since(start));
+        let report = RetryError::new(err, StopReason::Cancelled, attempts, elapsed);
+        // The span of the next attempt is already open, give up in the one of the last attempt.
+        let _retry = this.span.enter();
+        let _attempt = this.span.attempt(attempts).enter();
+        span::gave_up(&report);
+        this.notify.notify.on_give_up(&report);
+        Some(report.into_inner())
+    }
 }
 
-#[cfg(feature = "tokio")]
-fn rt_sleeper() -> impl Sleeper {
-    TokioSleeper
+// Passes the errors on to the wrapped [`Notify`]. With `clone` set, the errors are cloned:
+// all of them are kept with `history`, else only the last one.
+#[derive(Debug)]
+struct Recorder<N, E> {
+    notify: N,
+    clone: Option<fn(&E) -> E>,
+    history: bool,
+    errors: Vec<E>,
 }
 
-#[cfg(feature = "tokio")]
-#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+impl<N, E> Recorder<N, E> {
+    fn record(&mut self, err: &E) {
+        if let Some(clone) = self.clone {
+            if !self.history {
+                self.errors.clear();
+            }
+            self.errors.push(clone(err));
+        }
+    }
+}
 
-struct TokioSleeper;
-#[cfg(feature = "tokio")]
-#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
-impl Sleeper for TokioSleeper {
-    type Sleep = ::tokio_1::time::Sleep;
-    fn sleep(&self, dur: Duration) -> Self::Sleep {
-        ::tokio_1::time::sleep(dur)
+impl<N, E> Notify<E> for Recorder<N, E>
+where
+    N: Notify<E>,
+{
+    fn notify(&mut self, err: E, duration: Duration) {
+        self.record(&err);
+        self.notify.notify(err, duration);
+    }
+
+    fn notify_with_context(&mut self, err: E, duration: Duration, context: &RetryContext) {
+        self.record(&err);
+        self.notify.notify_with_context(err, duration, context);
+    }
+
+    fn on_success(&mut self, attempts: u32, elapsed: Duration) {
+        self.notify.on_success(attempts, elapsed)
+    }
+
+    fn on_give_up(&mut self, err: &RetryError<E>) {
+        self.notify.on_give_up(err)
+    }
+
+    fn on_deadline(&mut self, attempts: u32, elapsed: Duration) {
+        self.notify.on_deadline(attempts, elapsed)
     }
 }
 
-#[cfg(feature = "async-std")]
-#[cfg_attr(docsrs, doc(cfg(feature = "async-std")))]
-struct AsyncStdSleeper;
+/// AsyncNotify is [`Notify`] for notifications which need async work, like writing to an
+/// audit log, see [`Retry::with_async_notify`].
+///
+/// [`Retry`] polls the returned future to completion before the delay starts. The future is
+/// owned by [`Retry`]: dropping [`Retry`], e.g. on cancellation, also drops a pending
+/// notification without completing it.
+///
+/// It's implemented for closures `FnMut(&E, Duration) -> impl Future<Output = ()>`; the
+/// returned future can't borrow the error, clone the parts it needs.
+pub trait AsyncNotify<E> {
+    type Future: Future<Output = ()>;
 
-#[cfg(feature = "async-std")]
-#[cfg_attr(docsrs, doc(cfg(feature = "async-std")))]
-impl Sleeper for AsyncStdSleeper {
-    type Sleep = Pin<Box<dyn Future<Output = ()> + Send + 'static>>;
-    fn sleep(&self, dur: Duration) -> Self::Sleep {
-        Box::pin(::async_std_1::task::sleep(dur))
+    /// Called with the error of the failed attempt and the duration to wait before the next one.
+    fn notify(&mut self, err: &E, duration: Duration) -> Self::Future;
+
+    /// Same as [`notify`](#tymethod.notify), with additional information about the failed
+    /// attempt. Calls [`notify`](#tymethod.notify) by default.
+    fn notify_with_context(
+        &mut self,
+        err: &E,
+        duration: Duration,
+        _context: &RetryContext,
+    ) -> Self::Future {
+        self.notify(err, duration)
     }
 }
+
+impl<E, F, Fut> AsyncNotify<E> for F
+where
+    F: FnMut(&E, Duration) -> Fut,
+    Fut: Future<Output = ()>,
+{
+    type Future = Fut;
+
+    fn notify(&mut self, err: &E, duration: Duration) -> Self::Future {
+        self(err, duration)
+    }
+}
+
+/// The hooks of a [`Retry`] with an [`AsyncNotify`], see [`Retry::with_async_notify`].
+#[derive(Debug)]
+pub struct AsyncNotifier<A, F, H> {
+    notify: A,
+    pending: Option<Pin<Box<F>>>,
+    hooks: H,
+}
+
+impl<A, F, H, E> Hooks<E> for AsyncNotifier<A, F, H>
+where
+    A: AsyncNotify<E, Future = F>,
+    F: Future<Output = ()>,
+    H: Hooks<E>,
+{
+    fn notify(&mut self, err: &E, duration: Duration, context: &RetryContext) {
+        self.hooks.notify(err, duration, context);
+        self.pending = Some(Box::pin(
+            self.notify.notify_with_context(err, duration, context),
+        ));
+    }
+
+    fn poll_notified(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        ready!(self.hooks.poll_notified(cx));
+        if let Some(pending) = &mut self.pending {
+            ready!(pending.as_mut().poll(cx));
+            self.pending = None;
+        }
+        Poll::Ready(())
+    }
+
+    fn poll_decision(&mut self, err: &E, cx: &mut Context<'_>) -> Poll<Decision> {
+        self.hooks.poll_decision(err, cx)
+    }
+}
+
+/// The hooks of a [`Retry`] with an [`AsyncClassify`], see [`Retry::with_async_classifier`].
+#[derive(Debug)]
+pub struct AsyncClassifier<C, F, H> {
+    classify: C,
+    pending: Option<Pin<Box<F>>>,
+    hooks: H,
+}
+
+impl<C, F, H, E> Hooks<E> for AsyncClassifier<C, F, H>
+where
+    C: AsyncClassify<E, Future = F>,
+    F: Future<Output = Decision>,
+    H: Hooks<E>,
+{
+    fn notify(&mut self, err: &E, duration: Duration, context: &RetryContext) {
+        self.hooks.notify(err, duration, context)
+    }
+
+    fn poll_notified(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        self.hooks.poll_notified(cx)
+    }
+
+    fn poll_decision(&mut self, err: &E, cx: &mut Context<'_>) -> Poll<Decision> {
+        let pending = match &mut self.pending {
+            Some(pending) => pending,
+            None => match ready!(self.hooks.poll_decision(err, cx)) {
+                Decision::Transient => self.pending.insert(Box::pin(self.classify.classify(err))),
+                decision => return Poll::Ready(decision),
+            },
+        };
+        let decision = ready!(pending.as_mut().poll(cx));
+        self.pending = None;
+        Poll::Ready(decision)
+    }
+}
+
+mod hooks {
+    use super::*;
+
+    // The async hooks of [`Retry`], called along with its [`Notify`]. It's public in a private
+    // module, so it can bound the public types without being implemented outside this crate.
+    pub trait Hooks<E> {
+        // Starts the async notifications of a failed attempt.
+        fn notify(&mut self, err: &E, duration: Duration, context: &RetryContext);
+
+        // Polls the pending notifications to completion.
+        fn poll_notified(&mut self, cx: &mut Context<'_>) -> Poll<()>;
+
+        // Polls the decision on the transient error of an attempt.
+        fn poll_decision(&mut self, err: &E, cx: &mut Context<'_>) -> Poll<Decision>;
+    }
+
+    impl<E> Hooks<E> for () {
+        fn notify(&mut self, _err: &E, _duration: Duration, _context: &RetryContext) {}
+
+        fn poll_notified(&mut self, _cx: &mut Context<'_>) -> Poll<()> {
+            Poll::Ready(())
+        }
+
+        fn poll_decision(&mut self, _err: &E, _cx: &mut Context<'_>) -> Poll<Decision> {
+            Poll::Ready(Decision::Transient)
+        }
+    }
+}
+
+use hooks::Hooks;
+
+pin_project! {
+    /// A [`Retry`] which reports why it gave up, see [`Retry::report`].
+    pub struct Report<S: Sleeper, B, N, Fn, Fut: Future, C, E, H = ()> {
+        // The retry loop, recording the errors for [`RetryError::history`].
+        #[pin]
+        retry: Retry<S, B, Recorder<N, E>, Fn, Fut, C, H>,
+    }
+}
+
+impl<S, B, N, Fn, Fut, C, H, I, E> Future for Report<S, B, N, Fn, Fut, C, E, H>
+where
+    S: Sleeper,
+    B: Backoff,
+    C: Clock,
+    N: Notify<E>,
+    H: Hooks<E>,
+    Fn: FnMut() -> Fut,
+    Fut: Future<Output = Result<I, Error<E>>>,
+    E: Traced,
+{
+    type Output = RetryResult<I, E>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut retry = self.project().retry;
+        let res = ready!(retry.as_mut().poll_report(cx));
+        Poll::Ready(res.map_err(|report| RetryError {
+            history: mem::take(&mut retry.project().notify.errors),
+            ..report
+        }))
+    }
+}
+
+pin_project! {
+    /// A [`Retry`] which can be cancelled, see [`Retry::with_cancel`].
+    pub struct Cancellable<S: Sleeper, B, N, Fn, Fut: Future, C, X, E, H = ()> {
+        // The retry loop, keeping the last error for [`CancelError::Cancelled`].
+        #[pin]
+        retry: Retry<S, B, Recorder<N, E>, Fn, Fut, C, H>,
+
+        // [`Future`] which completes when the loop should be cancelled.
+        #[pin]
+        cancel: X,
+
+        // Whether `cancel` has completed.
+        cancelled: bool,
+    }
+}
+
+impl<S, B, N, Fn, Fut, C, X, H, I, E> Future for Cancellable<S, B, N, Fn, Fut, C, X, E, H>
+where
+    S: Sleeper,
+    B: Backoff,
+    C: Clock,
+    N: Notify<E>,
+    H: Hooks<E>,
+    Fn: FnMut() -> Fut,
+    Fut: Future<Output = Result<I, Error<E>>>,
+    X: Future<Output = ()>,
+    E: Clone + Traced,
+{
+    type Output = Result<I, CancelError<E>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut this = self.project();
+
+        if !*this.cancelled && this.cancel.poll(cx).is_ready() {
+            *this.cancelled = true;
+        }
+
+        if *this.cancelled {
+            if let Some(err) = this.retry.as_mut().take_last_error() {
+                return Poll::Ready(Err(CancelError::Cancelled(err)));
+            }
+        }
+
+        match this.retry.as_mut().poll(cx) {
+            Poll::Ready(res) => Poll::Ready(res.map_err(CancelError::Failed)),
+            Poll::Pending if *this.cancelled => match this.retry.take_last_error() {
+                Some(err) => Poll::Ready(Err(CancelError::Cancelled(err))),
+                None => Poll::Pending,
+            },
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+pin_project! {
+    /// A [`Retry`] with a deadline, see [`Retry::with_deadline`].
+    pub struct Deadlined<S: Sleeper, B, N, Fn, Fut: Future, C, H> {
+        // The retry loop, cancelling the attempt running at the deadline.
+        #[pin]
+        retry: Retry<S, B, N, Fn, Fut, C, H>,
+    }
+}
+
+impl<S, B, N, Fn, Fut, C, H, I, E> Future for Deadlined<S, B, N, Fn, Fut, C, H>
+where
+    S: Sleeper,
+    B: Backoff,
+    C: Clock,
+    N: Notify<E>,
+    H: Hooks<E>,
+    Fn: FnMut() -> Fut,
+    Fut: Future<Output = Result<I, Error<E>>>,
+    E: Traced,
+{
+    type Output = Result<I, DeadlineError<E>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let res = ready!(self.project().retry.poll_loop(cx));
+        Poll::Ready(res.map_err(|report| match report {
+            None => DeadlineError::Exceeded(None),
+            Some(report) if report.reason == StopReason::Deadline => {
+                DeadlineError::Exceeded(Some(report.err))
+            }
+            Some(report) => DeadlineError::Failed(report.err),
+        }))
+    }
+}
+
+/// Wraps `operation`, so every attempt it starts is cancelled if it doesn't complete within
+/// `timeout`. The timeout starts when the attempt is first polled.
+///
+/// A timed out attempt fails with the transient [`TimeoutError::TimedOut`], the errors of the
+/// operation are wrapped into [`TimeoutError::Operation`].
+///
+/// # Example
+///
+/// ```rust
+/// # #[cfg(feature = "tokio")]
+/// # {
+/// use backoff::backoff::{BackoffExt, Constant};
+/// use backoff::future::{retry, timeout};
+/// use backoff::tokio::TokioSleeper;
+/// use backoff::TimeoutError;
+/// use std::time::Duration;
+///
+/// async fn f() -> Result<(), backoff::Error<&'static str>> {
+///     // An attempt which hangs...
+///     std::future::pending().await
+/// }
+///
+/// # async fn go() {
+/// let backoff = Constant::new(Duration::from_millis(10)).with_max_retries(2);
+/// let operation = timeout(TokioSleeper, Duration::from_millis(10), f);
+/// let err = retry(backoff, operation).await.err().unwrap();
+/// assert_eq!(err, TimeoutError::TimedOut(Duration::from_millis(10)));
+/// # }
+/// # tokio_1::runtime::Runtime::new().unwrap().block_on(go());
+/// # }
+/// ```
+pub fn timeout<S, Fn, Fut, I, E>(
+    sleeper: S,
+    timeout: Duration,
+    mut operation: Fn,
+) -> impl FnMut() -> Timeout<Fut, S>
+where
+    S: Sleeper + Clone,
+    Fn: FnMut() -> Fut,
+    Fut: Future<Output = Result<I, Error<E>>>,
+{
+    move || Timeout::new(operation(), sleeper.clone(), timeout)
+}
+
+pin_project! {
+    /// A single attempt which is cancelled if it doesn't complete in time, see [`timeout`].
+    pub struct Timeout<Fut, S: Sleeper> {
+        // The attempt.
+        #[pin]
+        fut: Fut,
+
+        // The [`Sleeper`] that we generate the `sleep` future from.
+        sleeper: S,
+
+        // The duration the attempt is allowed to take.
+        timeout: Duration,
+
+        // [`Future`] which completes when the attempt times out, created on the first poll.
+        #[pin]
+        sleep: OptionPinned<S::Sleep>,
+    }
+}
+
+impl<Fut, S> Timeout<Fut, S>
+where
+    S: Sleeper,
+{
+    pub fn new(fut: Fut, sleeper: S, timeout: Duration) -> Self {
+        Timeout {
+            fut,
+            sleeper,
+            timeout,
+            sleep: OptionPinned::None,
+        }
+    }
+}
+
+impl<Fut, S, I, E> Future for Timeout<Fut, S>
+where
+    S: Sleeper,
+    Fut: Future<Output = Result<I, Error<E>>>,
+{
+    type Output = Result<I, Error<TimeoutError<E>>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut this = self.project();
+
+        if let Poll::Ready(res) = this.fut.poll(cx) {
+            return Poll::Ready(res.map_err(|err| match err {
+                Error::Permanent(err) => Error::Permanent(TimeoutError::Operation(err)),
+                Error::Transient { err, retry_after } => Error::Transient {
+                    err: TimeoutError::Operation(err),
+                    retry_after,
+                },
+            }));
+        }
+
+        if let OptionProj::None = this.sleep.as_mut().project() {
+            this.sleep.set(OptionPinned::Some {
+                inner: this.sleeper.sleep(*this.timeout),
+            });
+        }
+
+        if let OptionProj::Some { inner: sleep } = this.sleep.project() {
+            ready!(sleep.poll(cx));
+        }
+        Poll::Ready(Err(Error::transient(TimeoutError::TimedOut(*this.timeout))))
+    }
+}
+
+#[cfg(feature = "tokio")]
+pub use crate::tokio::TokioSleeper;
+
+#[cfg(feature = "async-std")]
+pub use crate::async_std::AsyncStdSleeper;
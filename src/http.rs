@@ -0,0 +1,215 @@
+//! Helpers for retrying HTTP requests.
+//!
+//! [`parse_retry_after`] turns the value of a `Retry-After` header into the duration to pass to
+//! [`Error::retry_after`](../enum.Error.html#method.retry_after). With the `http` feature flag,
+//! `classify` decides how a failed response should be retried from its status and headers.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[cfg(feature = "http")]
+use crate::classify::Decision;
+
+/// Parses the value of a `Retry-After` header, which is either a number of seconds or an
+/// HTTP-date. Dates in the past give a zero duration.
+///
+/// # Examples
+///
+/// ```rust
+/// # use backoff::http::parse_retry_after;
+/// # use std::time::Duration;
+/// assert_eq!(parse_retry_after("120"), Some(Duration::from_secs(120)));
+/// assert_eq!(parse_retry_after("Sun, 06 Nov 1994 08:49:37 GMT"), Some(Duration::ZERO));
+/// assert_eq!(parse_retry_after("soon"), None);
+/// ```
+pub fn parse_retry_after(value: &str) -> Option<Duration> {
+    parse_retry_after_at(value, SystemTime::now())
+}
+
+fn parse_retry_after_at(value: &str, now: SystemTime) -> Option<Duration> {
+    let value = value.trim();
+    if !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit()) {
+        return value.parse().ok().map(Duration::from_secs);
+    }
+
+    let date = parse_http_date(value)?;
+    Some(date.duration_since(now).unwrap_or_default())
+}
+
+// Parses the three formats of RFC 9110, section 5.6.7:
+// IMF-fixdate `Sun, 06 Nov 1994 08:49:37 GMT`, RFC 850 `Sunday, 06-Nov-94 08:49:37 GMT`
+// and asctime `Sun Nov  6 08:49:37 1994`.
+fn parse_http_date(value: &str) -> Option<SystemTime> {
+    let fields: Vec<&str> = value.split_whitespace().collect();
+    let (day, month, year, time) = match fields[..] {
+        [_, day, month, year, time, "GMT"] => (day, month, year.parse().ok()?, time),
+        [_, date, time, "GMT"] => {
+            let mut date = date.split('-');
+            let (day, month, year) = (date.next()?, date.next()?, date.next()?);
+            let year: u64 = year.parse().ok()?;
+            // Two digit years are in the past 100 years, close enough to RFC 9110.
+            let year = match year {
+                0..=69 => 2000 + year,
+                70..=99 => 1900 + year,
+                _ => return None,
+            };
+            (day, month, year, time)
+        }
+        [_, month, day, time, year] => (day, month, year.parse().ok()?, time),
+        _ => return None,
+    };
+
+    let month = match month {
+        "Jan" => 1,
+        "Feb" => 2,
+        "Mar" => 3,
+        "Apr" => 4,
+        "May" => 5,
+        "Jun" => 6,
+        "Jul" => 7,
+        "Aug" => 8,
+        "Sep" => 9,
+        "Oct" => 10,
+        "Nov" => 11,
+        "Dec" => 12,
+        _ => return None,
+    };
+    let day: u64 = day.parse().ok()?;
+    let mut time = time.split(':').map(|field| field.parse::<u64>().ok());
+    let (hours, minutes, seconds) = (time.next()??, time.next()??, time.next()??);
+
+    if !(1970..=9999).contains(&year)
+        || !(1..=days_in_month(year, month)).contains(&day)
+        || hours > 23
+        || minutes > 59
+        || seconds > 60
+    {
+        return None;
+    }
+
+    let secs = days_since_epoch(year, month, day)
+        .checked_mul(86400)?
+        .checked_add(hours * 3600 + minutes * 60 + seconds)?;
+    UNIX_EPOCH.checked_add(Duration::from_secs(secs))
+}
+
+// `u64::is_multiple_of` needs Rust 1.87.
+#[allow(unknown_lints, clippy::manual_is_multiple_of)]
+fn days_in_month(year: u64, month: u64) -> u64 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+// Days from 1970-01-01 to the given date of the proleptic Gregorian calendar, see
+// http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+fn days_since_epoch(year: u64, month: u64, day: u64) -> u64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year / 400;
+    let year_of_era = year - era * 400;
+    let month = if month > 2 { month - 3 } else { month + 9 };
+    let day_of_year = (153 * month + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Decides how a request which failed with `status` should be retried.
+///
+/// `429 Too Many Requests`, `502 Bad Gateway`, `503 Service Unavailable` and
+/// `504 Gateway Timeout` are retried, after the duration of the `Retry-After` header if
+/// present. Other `4xx` statuses are permanent errors, anything else is transient.
+///
+/// Only available through the `http` feature flag.
+///
+/// # Examples
+///
+/// ```rust
+/// # use backoff::classify::Decision;
+/// # use backoff::http::classify;
+/// # use http_0_2::{header, HeaderMap, HeaderValue, StatusCode};
+/// # use std::time::Duration;
+/// let mut headers = HeaderMap::new();
+/// headers.insert(header::RETRY_AFTER, HeaderValue::from_static("30"));
+///
+/// assert_eq!(
+///     classify(StatusCode::TOO_MANY_REQUESTS, &headers),
+///     Decision::RetryAfter(Duration::from_secs(30))
+/// );
+/// assert_eq!(classify(StatusCode::NOT_FOUND, &headers), Decision::Permanent);
+/// ```
+#[cfg(feature = "http")]
+#[cfg_attr(docsrs, doc(cfg(feature = "http")))]
+pub fn classify(status: http_0_2::StatusCode, headers: &http_0_2::HeaderMap) -> Decision {
+    use http_0_2::{header::RETRY_AFTER, StatusCode};
+
+    match status {
+        StatusCode::TOO_MANY_REQUESTS
+        | StatusCode::BAD_GATEWAY
+        | StatusCode::SERVICE_UNAVAILABLE
+        | StatusCode::GATEWAY_TIMEOUT => headers
+            .get(RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(parse_retry_after)
+            .map_or(Decision::Transient, Decision::RetryAfter),
+        status if status.is_client_error() => Decision::Permanent,
+        _ => Decision::Transient,
+    }
+}
+
+#[test]
+fn parse_retry_after_seconds() {
+    assert_eq!(parse_retry_after(" 0 "), Some(Duration::ZERO));
+    assert_eq!(parse_retry_after("86400"), Some(Duration::from_secs(86400)));
+    assert_eq!(parse_retry_after("-1"), None);
+    assert_eq!(parse_retry_after("1.5"), None);
+    assert_eq!(parse_retry_after(""), None);
+}
+
+#[test]
+fn parse_retry_after_date() {
+    // Sun, 06 Nov 1994 08:49:37 GMT
+    let date = UNIX_EPOCH + Duration::from_secs(784_111_777);
+    let now = date - Duration::from_secs(90);
+
+    for value in &[
+        "Sun, 06 Nov 1994 08:49:37 GMT",
+        "Sunday, 06-Nov-94 08:49:37 GMT",
+        "Sun Nov  6 08:49:37 1994",
+    ] {
+        assert_eq!(parse_http_date(value), Some(date), "{}", value);
+        assert_eq!(
+            parse_retry_after_at(value, now),
+            Some(Duration::from_secs(90))
+        );
+    }
+
+    let later = date + Duration::from_secs(1);
+    assert_eq!(
+        parse_retry_after_at("Sun, 06 Nov 1994 08:49:37 GMT", later),
+        Some(Duration::ZERO)
+    );
+    assert_eq!(parse_http_date("Sun, 06 Foo 1994 08:49:37 GMT"), None);
+    assert_eq!(parse_http_date("Sun, 06 Nov 1994 08:49 GMT"), None);
+}
+
+#[test]
+fn parse_http_date_out_of_range() {
+    assert_eq!(
+        parse_http_date("Sun, 06 Nov 99999999999999 08:49:37 GMT"),
+        None
+    );
+    assert_eq!(parse_http_date("Sun Nov  6 08:49:37 999999999999"), None);
+    assert_eq!(
+        parse_http_date("Sunday, 06-Nov-999999999999 08:49:37 GMT"),
+        None
+    );
+    assert_eq!(parse_http_date("Fri, 01 Jan 10000 00:00:00 GMT"), None);
+    assert_eq!(parse_http_date("Mon, 31 Feb 2025 08:49:37 GMT"), None);
+    assert_eq!(parse_http_date("Sat, 29 Feb 2025 08:49:37 GMT"), None);
+    assert_eq!(parse_http_date("Wed, 31 Apr 2025 08:49:37 GMT"), None);
+
+    assert!(parse_http_date("Thu, 29 Feb 2024 08:49:37 GMT").is_some());
+    assert!(parse_http_date("Fri, 31 Dec 9999 23:59:59 GMT").is_some());
+}
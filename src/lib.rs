@@ -30,6 +30,11 @@
 //!
 //! **Note**: `max_interval` caps the `retry_interval` and not the randomized interval.
 //!
+//! The formula above describes the default, symmetric jitter. Other strategies can be
+//! picked with [`Jitter`](exponential/enum.Jitter.html): full jitter (a random value between
+//! zero and `retry_interval`), equal jitter (half of `retry_interval` plus a random value
+//! up to the other half) or no jitter at all.
+//!
 //! If the time elapsed since an [`ExponentialBackoff`](type.ExponentialBackoff.html) instance is created goes past the
 //! `max_elapsed_time`, then the method [`next_backoff`] starts returning `None`.
 //!
@@ -173,7 +178,10 @@
 //!
 //! ### Async
 //!
-//! Please set either the `tokio` or `async-std` features in Cargo.toml to enable the async support of this library, i.e.:
+//! Please set the `tokio` or `async-std` features in Cargo.toml to enable the async support of this library.
+//! Both can be enabled at the same time, `backoff::tokio` and `backoff::async_std` retry on the respective
+//! runtime, while `backoff::future::retry` uses tokio whenever the `tokio` feature is enabled, and is
+//! deprecated when both are, i.e.:
 //!
 //! ```toml
 //! backoff = { version = "x.y.z", features = ["tokio"] }
@@ -190,7 +198,7 @@
 //! use backoff::ExponentialBackoff;
 //!
 //! async fn fetch_url(url: &str) -> Result<String, reqwest::Error> {
-//!     backoff::future::retry(ExponentialBackoff::default(), || async {
+//!     backoff::tokio::retry(ExponentialBackoff::default(), || async {
 //!         println!("Fetching {}", url);
 //!         Ok(reqwest::get(url).await?.text().await?)
 //!     })
@@ -205,28 +213,88 @@
 //!     }
 //! }
 //! ```
+//!
+//! ## Reports
+//!
+//! For historical reasons, `retry` returns the last error as `Error<E>`, while
+//! `future::retry` resolves to the bare `E`. Both loops can report the same
+//! [`RetryResult`](type.RetryResult.html) instead, with a [`RetryError`](struct.RetryError.html)
+//! telling the number of attempts, the elapsed time and why the loop gave up:
+//!
+//! ```rust
+//! use backoff::backoff::Stop;
+//! use backoff::{Error, Retry, StopReason};
+//!
+//! let report = Retry::new(Stop {})
+//!     .retry_report(|| Err::<(), _>(Error::transient("error")))
+//!     .unwrap_err();
+//! assert_eq!(report.reason, StopReason::Exhausted);
+//! assert_eq!(report.attempts, 1);
+//! ```
+//!
+//! The async counterpart is `future::Retry::report`. The plain signatures are thin wrappers
+//! over the reports, see [`RetryError::into_error`](struct.RetryError.html#method.into_error) and
+//! [`RetryError::into_inner`](struct.RetryError.html#method.into_inner).
+//!
 //! # Feature flags
 //!
-//! - `futures`: enables futures support,
-//! - `tokio`: enables support for the [tokio](https://crates.io/crates/tokio) async runtime, implies `futures`,
-//! - `async-std`: enables support for the [async-std](https://crates.io/crates/async-std) async runtime, implies `futures`,
-//! - `wasm-bindgen`: enabled support for [wasm-bindgen](https://crates.io/crates/wasm-bindgen).
+//! - `futures`: enables futures support, with a custom [`Sleeper`](future/trait.Sleeper.html) passed to
+//!   [`future::retry_with_sleeper`](future/fn.retry_with_sleeper.html),
+//! - `tokio`: enables support for the [tokio](https://crates.io/crates/tokio) async runtime in the
+//!   [`tokio`](tokio/index.html) module, implies `futures`,
+//! - `async-std`: enables support for the [async-std](https://crates.io/crates/async-std) async runtime in the
+//!   [`async_std`](async_std/index.html) module, implies `futures`,
+//! - `wasm-bindgen`: enabled support for [wasm-bindgen](https://crates.io/crates/wasm-bindgen),
+//! - `testing`: enables the [`testing`](testing/index.html) module with a virtual clock for testing retry logic,
+//! - `http`: enables [`http::classify`](http/fn.classify.html), deciding how failed HTTP responses are retried,
+//! - `tracing`: records the retry loops, their attempts and why they gave up with
+//!   [tracing](https://crates.io/crates/tracing) spans and events, see the [`tracing`](tracing/index.html) module,
+//! - `log`: enables [`log::LogNotify`](log/struct.LogNotify.html), logging the failed attempts with
+//!   [log](https://crates.io/crates/log).
 
 pub mod backoff;
+pub mod classify;
 mod clock;
 pub mod default;
 mod error;
 pub mod exponential;
+pub mod http;
 
 #[cfg(feature = "futures")]
 #[cfg_attr(docsrs, doc(cfg(feature = "futures")))]
 pub mod future;
 
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+pub mod tokio;
+
+#[cfg(feature = "async-std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async-std")))]
+pub mod async_std;
+
 mod retry;
+mod span;
+
+#[cfg(feature = "testing")]
+#[cfg_attr(docsrs, doc(cfg(feature = "testing")))]
+pub mod testing;
+
+#[cfg(feature = "tracing")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tracing")))]
+pub mod tracing;
+
+#[cfg(feature = "log")]
+#[cfg_attr(docsrs, doc(cfg(feature = "log")))]
+pub mod log;
 
 pub use crate::clock::{Clock, SystemClock};
-pub use crate::error::Error;
-pub use crate::retry::{retry, retry_notify, Notify};
+pub use crate::error::{
+    CancelError, DeadlineError, Error, RetryError, RetryResult, StopReason, TimeoutError,
+};
+pub use crate::retry::{
+    retry, retry_if, retry_notify, retry_notify_with_sleep, retry_with_sleep, CancelToken, Combine,
+    Deadline, NoopNotify, Notify, Retry, RetryAfterPolicy, RetryContext, Sleep, ThreadSleep,
+};
 
 /// Exponential backoff policy with system's clock.
 ///
@@ -237,3 +305,9 @@ pub type ExponentialBackoff = exponential::ExponentialBackoff<SystemClock>;
 
 /// Builder for exponential backoff policy with system's clock.
 pub type ExponentialBackoffBuilder = exponential::ExponentialBackoffBuilder<SystemClock>;
+
+/// Decorrelated jitter backoff policy with system's clock.
+pub type DecorrelatedJitter = backoff::DecorrelatedJitter<SystemClock>;
+
+/// Builder for decorrelated jitter backoff policy with system's clock.
+pub type DecorrelatedJitterBuilder = backoff::DecorrelatedJitterBuilder<SystemClock>;
@@ -0,0 +1,105 @@
+//! Logging the failed attempts with [log](https://crates.io/crates/log).
+
+use std::fmt::Display;
+use std::time::Duration;
+
+use log_0_4::{log, Level};
+
+use crate::error::{RetryError, StopReason};
+use crate::retry::{Notify, RetryContext};
+
+/// [`Notify`] logging the failed attempts with [log](https://crates.io/crates/log), usable
+/// wherever [`NoopNotify`](../struct.NoopNotify.html) is.
+///
+/// Every failed attempt is logged at `level` to `target` as
+/// `{prefix}: {err}, retrying in {duration:?} (attempt {attempt})`, and giving up as
+/// `{prefix}: {err}, giving up after {attempts} attempt(s) ({reason})`, without the error if
+/// `future::Retry` gave up at its deadline with no error to report. By default it logs at the
+/// `WARN` level to the `backoff` target, with the `transient error` prefix.
+///
+/// # Example
+///
+/// ```rust
+/// use backoff::log::LogNotify;
+/// use backoff::ExponentialBackoff;
+/// use log_0_4::Level;
+///
+/// fn fetch() -> Result<(), backoff::Error<std::io::Error>> {
+///     // Business logic...
+///     Ok(())
+/// }
+///
+/// let notify = LogNotify {
+///     level: Level::Info,
+///     target: "my_app::sync",
+///     prefix: "fetching failed",
+/// };
+/// backoff::retry_notify(ExponentialBackoff::default(), fetch, notify).unwrap();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LogNotify {
+    /// The level of the log records.
+    pub level: Level,
+    /// The target of the log records.
+    pub target: &'static str,
+    /// The start of the log messages, followed by the error.
+    pub prefix: &'static str,
+}
+
+impl Default for LogNotify {
+    fn default() -> Self {
+        LogNotify {
+            level: Level::Warn,
+            target: "backoff",
+            prefix: "transient error",
+        }
+    }
+}
+
+impl<E: Display> Notify<E> for LogNotify {
+    fn notify(&mut self, err: E, duration: Duration) {
+        log!(
+            target: self.target,
+            self.level,
+            "{}: {}, retrying in {:?}",
+            self.prefix,
+            err,
+            duration
+        );
+    }
+
+    fn notify_with_context(&mut self, err: E, duration: Duration, context: &RetryContext) {
+        log!(
+            target: self.target,
+            self.level,
+            "{}: {}, retrying in {:?} (attempt {})",
+            self.prefix,
+            err,
+            duration,
+            context.attempt
+        );
+    }
+
+    fn on_give_up(&mut self, err: &RetryError<E>) {
+        log!(
+            target: self.target,
+            self.level,
+            "{}: {}, giving up after {} attempt(s) ({})",
+            self.prefix,
+            err.err,
+            err.attempts,
+            err.reason
+        );
+    }
+
+    fn on_deadline(&mut self, attempts: u32, _elapsed: Duration) {
+        log!(
+            target: self.target,
+            self.level,
+            "{}: giving up after {} attempt(s) ({})",
+            self.prefix,
+            attempts,
+            StopReason::Deadline
+        );
+    }
+}
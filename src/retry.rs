@@ -1,8 +1,14 @@
+use std::sync::{Arc, Condvar, Mutex};
 use std::thread;
 use std::time::Duration;
 
+use instant::Instant;
+
 use crate::backoff::Backoff;
-use crate::error::Error;
+use crate::classify::Classify;
+use crate::clock::{Clock, SystemClock};
+use crate::error::{CancelError, Error, RetryError, RetryResult, StopReason};
+use crate::span::{self, Span, Traced};
 
 /// Retries this operation according to the backoff policy.
 /// backoff is reset before it is used.
@@ -23,15 +29,19 @@ use crate::error::Error;
 pub fn retry<F, B, T, E>(backoff: B, op: F) -> Result<T, Error<E>>
 where
     F: FnMut() -> Result<T, Error<E>>,
+    E: Traced,
     B: Backoff,
 {
     let mut retry = Retry {
         backoff,
         notify: NoopNotify,
         sleep: ThreadSleep,
+        clock: SystemClock::default(),
+        deadline: None,
+        retry_after: RetryAfterPolicy::default(),
     };
 
-    retry.retry_notify(op)
+    retry.retry(op)
 }
 
 /// Retries this operation according to the backoff policy.
@@ -56,6 +66,7 @@ where
 pub fn retry_notify<F, B, N, T, E>(backoff: B, op: F, notify: N) -> Result<T, Error<E>>
 where
     F: FnMut() -> Result<T, Error<E>>,
+    E: Traced,
     B: Backoff,
     N: Notify<E>,
 {
@@ -63,65 +74,663 @@ where
         backoff,
         notify,
         sleep: ThreadSleep,
+        clock: SystemClock::default(),
+        deadline: None,
+        retry_after: RetryAfterPolicy::default(),
     };
 
-    retry.retry_notify(op)
+    retry.retry(op)
+}
+
+/// Retries this operation according to the backoff policy, with `classify` deciding
+/// which of its errors are retried. Unlike [`retry`], the operation returns plain errors.
+/// backoff is reset before it is used.
+///
+/// # Examples
+///
+/// ```rust
+/// # use backoff::{retry_if, Error, ExponentialBackoff};
+/// # use backoff::classify::Decision;
+/// let f = || -> Result<(), &str> { Err("not found") };
+///
+/// let classify = |err: &&str| match *err {
+///     "not found" => Decision::Permanent,
+///     _ => Decision::Transient,
+/// };
+/// let err = retry_if(ExponentialBackoff::default(), f, classify).unwrap_err();
+/// assert_eq!(err, Error::Permanent("not found"));
+/// ```
+pub fn retry_if<F, B, C, T, E>(backoff: B, mut op: F, classify: C) -> Result<T, Error<E>>
+where
+    F: FnMut() -> Result<T, E>,
+    E: Traced,
+    B: Backoff,
+    C: Classify<E>,
+{
+    retry(backoff, || op().map_err(|err| classify.error(err)))
 }
 
-struct Retry<B, N, S> {
+/// Retries this operation according to the backoff policy, waiting between the attempts
+/// with `sleep` instead of blocking the thread.
+/// backoff is reset before it is used.
+///
+/// # Examples
+///
+/// ```rust
+/// # use backoff::{Error, retry_with_sleep};
+/// # use backoff::backoff::{BackoffExt, Constant};
+/// # use std::time::Duration;
+/// let mut slept = Vec::new();
+/// let f = || -> Result<(), Error<&str>> { Err(Error::transient("error")) };
+///
+/// let backoff = Constant::new(Duration::from_secs(1)).with_max_retries(2);
+/// let _ = retry_with_sleep(backoff, f, |dur| slept.push(dur));
+/// assert_eq!(slept, vec![Duration::from_secs(1); 2]);
+/// ```
+pub fn retry_with_sleep<F, B, S, T, E>(backoff: B, op: F, sleep: S) -> Result<T, Error<E>>
+where
+    F: FnMut() -> Result<T, Error<E>>,
+    E: Traced,
+    B: Backoff,
+    S: Sleep,
+{
+    let mut retry = Retry {
+        backoff,
+        notify: NoopNotify,
+        sleep,
+        clock: SystemClock::default(),
+        deadline: None,
+        retry_after: RetryAfterPolicy::default(),
+    };
+
+    retry.retry(op)
+}
+
+/// Retries this operation according to the backoff policy, waiting between the attempts
+/// with `sleep` instead of blocking the thread.
+/// Calls notify on failed attempts (in case of transient errors).
+/// backoff is reset before it is used.
+pub fn retry_notify_with_sleep<F, B, N, S, T, E>(
+    backoff: B,
+    op: F,
+    notify: N,
+    sleep: S,
+) -> Result<T, Error<E>>
+where
+    F: FnMut() -> Result<T, Error<E>>,
+    E: Traced,
+    B: Backoff,
+    N: Notify<E>,
+    S: Sleep,
+{
+    let mut retry = Retry {
+        backoff,
+        notify,
+        sleep,
+        clock: SystemClock::default(),
+        deadline: None,
+        retry_after: RetryAfterPolicy::default(),
+    };
+
+    retry.retry(op)
+}
+
+/// Retry is the blocking retry loop behind [`retry`] and [`retry_notify`], for when
+/// more of it needs to be configured.
+///
+/// # Examples
+///
+/// ```rust
+/// # use backoff::{Error, Retry};
+/// # use backoff::backoff::{BackoffExt, Constant};
+/// # use std::time::Duration;
+/// let f = || -> Result<(), Error<&str>> { Err(Error::transient("error")) };
+///
+/// let backoff = Constant::new(Duration::from_millis(10)).with_max_retries(3);
+/// let _ = Retry::new(backoff)
+///     .with_notify(|err, dur| println!("Error happened at {:?}: {}", dur, err))
+///     .with_sleep(|_| ())
+///     .retry(f);
+/// ```
+pub struct Retry<B, N = NoopNotify, S = ThreadSleep, C = SystemClock> {
     backoff: B,
     notify: N,
     sleep: S,
+    clock: C,
+    deadline: Option<Deadline>,
+    retry_after: RetryAfterPolicy,
 }
 
-impl<B, N, S> Retry<B, N, S> {
-    pub fn retry_notify<F, T, E>(&mut self, mut op: F) -> Result<T, Error<E>>
+impl<B> Retry<B> {
+    /// Creates a retry loop with the given backoff policy, which waits with
+    /// [`ThreadSleep`] and doesn't notify about failed attempts.
+    pub fn new(backoff: B) -> Self {
+        Retry {
+            backoff,
+            notify: NoopNotify,
+            sleep: ThreadSleep,
+            clock: SystemClock::default(),
+            deadline: None,
+            retry_after: RetryAfterPolicy::default(),
+        }
+    }
+}
+
+impl<B, N, S, C> Retry<B, N, S, C> {
+    /// Calls `notify` on failed attempts (in case of transient errors).
+    pub fn with_notify<M>(self, notify: M) -> Retry<B, M, S, C> {
+        Retry {
+            backoff: self.backoff,
+            notify,
+            sleep: self.sleep,
+            clock: self.clock,
+            deadline: self.deadline,
+            retry_after: self.retry_after,
+        }
+    }
+
+    /// Waits between the attempts with `sleep`.
+    pub fn with_sleep<T>(self, sleep: T) -> Retry<B, N, T, C> {
+        Retry {
+            backoff: self.backoff,
+            notify: self.notify,
+            sleep,
+            clock: self.clock,
+            deadline: self.deadline,
+            retry_after: self.retry_after,
+        }
+    }
+
+    /// Measures the elapsed time with `clock`.
+    pub fn with_clock<D>(self, clock: D) -> Retry<B, N, S, D> {
+        Retry {
+            backoff: self.backoff,
+            notify: self.notify,
+            sleep: self.sleep,
+            clock,
+            deadline: self.deadline,
+            retry_after: self.retry_after,
+        }
+    }
+
+    /// Gives up once `deadline` is reached, whatever the backoff policy is.
+    ///
+    /// The deadline is checked between the attempts: if the next delay would reach the
+    /// deadline, the loop gives up right away with the error of the last attempt. A running
+    /// attempt can't be interrupted, so the operation should bound its own duration, e.g. with
+    /// I/O timeouts.
+    pub fn with_deadline<D: Into<Deadline>>(mut self, deadline: D) -> Self {
+        self.deadline = Some(deadline.into());
+        self
+    }
+
+    /// Uses the `retry_after` durations of the transient errors according to `policy`.
+    pub fn with_retry_after(mut self, policy: RetryAfterPolicy) -> Self {
+        self.retry_after = policy;
+        self
+    }
+
+    /// Retries this operation according to the backoff policy.
+    /// backoff is reset before it is used.
+    pub fn retry<F, T, E>(&mut self, op: F) -> Result<T, Error<E>>
+    where
+        F: FnMut() -> Result<T, Error<E>>,
+        E: Traced,
+        B: Backoff,
+        N: Notify<E>,
+        S: Sleep,
+        C: Clock,
+    {
+        self.run(op, None, None, false)
+            .map_err(RetryError::into_error)
+    }
+
+    /// Same as [`retry`](#method.retry), but reports how many attempts ran, how long they
+    /// took and why the loop gave up.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use backoff::{Error, Retry, StopReason};
+    /// # use backoff::backoff::{BackoffExt, Zero};
+    /// let f = || -> Result<(), Error<&str>> { Err(Error::transient("error")) };
+    ///
+    /// let report = Retry::new(Zero {}.with_max_retries(2)).retry_report(f).unwrap_err();
+    /// assert_eq!(report.err, "error");
+    /// assert_eq!(report.reason, StopReason::Exhausted);
+    /// assert_eq!(report.attempts, 3);
+    /// ```
+    pub fn retry_report<F, T, E>(&mut self, op: F) -> RetryResult<T, E>
+    where
+        F: FnMut() -> Result<T, Error<E>>,
+        E: Traced,
+        B: Backoff,
+        N: Notify<E>,
+        S: Sleep,
+        C: Clock,
+    {
+        self.run(op, None, None, false)
+    }
+
+    /// Same as [`retry_report`](#method.retry_report), with the errors of the attempts before
+    /// the last one recorded in [`RetryError::history`].
+    pub fn retry_report_with_history<F, T, E>(&mut self, op: F) -> RetryResult<T, E>
+    where
+        F: FnMut() -> Result<T, Error<E>>,
+        E: Clone + Traced,
+        B: Backoff,
+        N: Notify<E>,
+        S: Sleep,
+        C: Clock,
+    {
+        self.run(op, None, Some(E::clone), true)
+    }
+
+    /// Same as [`retry`](#method.retry), but stops before the next attempt once `cancel` is
+    /// cancelled, with [`CancelError::Cancelled`] holding the last error.
+    ///
+    /// The waits between the attempts are done with [`Sleep::sleep_cancellable`], which
+    /// [`ThreadSleep`] cuts short on cancellation. A running attempt isn't interrupted.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use backoff::{CancelError, CancelToken, Error, Retry};
+    /// # use backoff::backoff::Constant;
+    /// # use std::time::Duration;
+    /// let cancel = CancelToken::new();
+    /// let shutdown = cancel.clone();
+    /// let f = || -> Result<(), Error<&str>> {
+    ///     shutdown.cancel();
+    ///     Err(Error::transient("error"))
+    /// };
+    ///
+    /// let backoff = Constant::new(Duration::from_secs(60));
+    /// let err = Retry::new(backoff).retry_cancellable(f, &cancel).unwrap_err();
+    /// assert_eq!(err, CancelError::Cancelled(Error::transient("error")));
+    /// ```
+    pub fn retry_cancellable<F, T, E>(
+        &mut self,
+        op: F,
+        cancel: &CancelToken,
+    ) -> Result<T, CancelError<Error<E>>>
+    where
+        F: FnMut() -> Result<T, Error<E>>,
+        E: Clone + Traced,
+        B: Backoff,
+        N: Notify<E>,
+        S: Sleep,
+        C: Clock,
+    {
+        self.run(op, Some(cancel), Some(E::clone), false)
+            .map_err(|report| match report.reason {
+                StopReason::Cancelled => CancelError::Cancelled(Error::transient(report.err)),
+                _ => CancelError::Failed(report.into_error()),
+            })
+    }
+
+    // Runs the loop. With `clone` set, the errors passed to `notify` are cloned: all of them are
+    // kept with `history`, else only the last one, which `cancel` needs after the wait.
+    fn run<F, T, E>(
+        &mut self,
+        mut op: F,
+        cancel: Option<&CancelToken>,
+        clone: Option<fn(&E) -> E>,
+        history: bool,
+    ) -> RetryResult<T, E>
     where
         F: FnMut() -> Result<T, Error<E>>,
+        E: Traced,
         B: Backoff,
         N: Notify<E>,
         S: Sleep,
+        C: Clock,
     {
         self.backoff.reset();
 
-        loop {
-            let err = match op() {
-                Ok(v) => return Ok(v),
-                Err(err) => err,
-            };
+        let span = Span::retry();
+        let _retry = span.enter();
+        let start = self.clock.now();
+        let mut attempt = 0;
+        let mut errors = Vec::new();
+        let mut attempt_span;
+
+        let (err, reason) = loop {
+            attempt += 1;
+            attempt_span = span.attempt(attempt);
+            // The span of the attempt isn't entered while waiting for the next one.
+            let next = {
+                let _attempt = attempt_span.enter();
+                let attempt_start = self.clock.now();
+
+                let err = match op() {
+                    Ok(v) => {
+                        let elapsed = self.clock.now().duration_since(start);
+                        span::succeeded(attempt, elapsed);
+                        self.notify.on_success(attempt, elapsed);
+                        return Ok(v);
+                    }
+                    Err(err) => err,
+                };
+
+                let (err, next) = match err {
+                    Error::Permanent(err) => break (err, StopReason::Permanent),
+                    Error::Transient { err, retry_after } => {
+                        match self.retry_after.next(retry_after, &mut self.backoff) {
+                            Some(next) => (err, next),
+                            None => break (err, StopReason::Exhausted),
+                        }
+                    }
+                };
+
+                let now = self.clock.now();
+                if let Some(deadline) = self.deadline.and_then(|deadline| deadline.instant(start)) {
+                    if deadline.saturating_duration_since(now) <= next {
+                        break (err, StopReason::Deadline);
+                    }
+                }
+                if cancel.is_some_and(CancelToken::is_cancelled) {
+                    break (err, StopReason::Cancelled);
+                }
 
-            let (err, next) = match err {
-                Error::Permanent(err) => return Err(Error::Permanent(err)),
-                Error::Transient { err, retry_after } => {
-                    match retry_after.or_else(|| self.backoff.next_backoff()) {
-                        Some(next) => (err, next),
-                        None => return Err(Error::transient(err)),
+                if let Some(clone) = clone {
+                    if !history {
+                        errors.clear();
                     }
+                    errors.push(clone(&err));
+                }
+                let context = RetryContext {
+                    attempt,
+                    elapsed: now.duration_since(start),
+                    attempt_duration: now.duration_since(attempt_start),
+                };
+                span::failed(&err, next, attempt);
+                self.notify.notify_with_context(err, next, &context);
+                next
+            };
+
+            let cancelled = match cancel {
+                Some(cancel) => self.sleep.sleep_cancellable(next, cancel),
+                None => {
+                    self.sleep.sleep(next);
+                    false
                 }
             };
+            if cancelled {
+                if let Some(err) = errors.pop() {
+                    break (err, StopReason::Cancelled);
+                }
+            }
+        };
+
+        if !history {
+            errors.clear();
+        }
+        let elapsed = self.clock.now().duration_since(start);
+        let report = RetryError::new(err, reason, attempt, elapsed);
+        let _attempt = attempt_span.enter();
+        span::gave_up(&report);
+        self.notify.on_give_up(&report);
+        Err(RetryError {
+            history: errors,
+            ..report
+        })
+    }
+}
+
+/// CancelToken cancels the retry loops it's passed to, see
+/// [`Retry::retry_cancellable`]. Clones share the same state, so one clone can be
+/// cancelled from another thread while the loop waits on the other.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken {
+    inner: Arc<(Mutex<bool>, Condvar)>,
+}
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancels the token, waking up the loops waiting on it.
+    pub fn cancel(&self) {
+        let (cancelled, condvar) = &*self.inner;
+        *cancelled.lock().unwrap() = true;
+        condvar.notify_all();
+    }
+
+    /// Returns whether the token was cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        *self.inner.0.lock().unwrap()
+    }
+
+    // Waits for `dur`, or until the token is cancelled. Returns whether it was cancelled.
+    fn wait(&self, dur: Duration) -> bool {
+        let (cancelled, condvar) = &*self.inner;
+        let guard = cancelled.lock().unwrap();
+        let (guard, _) = condvar
+            .wait_timeout_while(guard, dur, |cancelled| !*cancelled)
+            .unwrap();
+        *guard
+    }
+}
+
+/// RetryAfterPolicy decides how the `retry_after` durations of transient errors, e.g. from
+/// the `Retry-After` headers of HTTP responses, are used.
+///
+/// By default they replace the delays of the backoff policy, which isn't asked at all.
+///
+/// # Examples
+///
+/// ```rust
+/// # use backoff::{Combine, RetryAfterPolicy};
+/// # use std::time::Duration;
+/// // Gives up on a server asking to come back after more than an hour, waits at most
+/// // a minute otherwise, and keeps to the backoff's elapsed time budget.
+/// let policy = RetryAfterPolicy {
+///     limit: Some(Duration::from_secs(3600)),
+///     cap: Some(Duration::from_secs(60)),
+///     combine: Combine::Max,
+/// };
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RetryAfterPolicy {
+    /// The loop gives up on durations longer than `limit`.
+    pub limit: Option<Duration>,
+
+    /// Durations longer than `cap` are shortened to it.
+    pub cap: Option<Duration>,
+
+    /// How the durations are combined with the delays of the backoff policy.
+    pub combine: Combine,
+}
+
+/// Combine tells how `retry_after` durations are combined with the delays of the backoff
+/// policy, see [`RetryAfterPolicy`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Combine {
+    /// The backoff policy isn't asked, so the duration doesn't count against its budget.
+    #[default]
+    Replace,
+
+    /// The backoff policy is still asked, and the loop gives up once it runs out, e.g. after
+    /// `max_elapsed_time`. Its delay is ignored.
+    Count,
+
+    /// Like `Count`, but waits for the longer of the duration and the delay of the backoff.
+    Max,
+}
+
+impl RetryAfterPolicy {
+    // Returns the delay before the next attempt, or `None` to give up.
+    pub(crate) fn next<B: Backoff>(
+        &self,
+        retry_after: Option<Duration>,
+        backoff: &mut B,
+    ) -> Option<Duration> {
+        let retry_after = match retry_after {
+            Some(retry_after) => retry_after,
+            None => return backoff.next_backoff(),
+        };
+        if self.limit.is_some_and(|limit| retry_after > limit) {
+            return None;
+        }
+        let retry_after = self.cap.map_or(retry_after, |cap| retry_after.min(cap));
 
-            self.notify.notify(err, next);
+        match self.combine {
+            Combine::Replace => Some(retry_after),
+            Combine::Count => backoff.next_backoff().map(|_| retry_after),
+            Combine::Max => backoff.next_backoff().map(|next| next.max(retry_after)),
+        }
+    }
+}
+
+/// Deadline bounds the total time of a retry loop, see [`Retry::with_deadline`] and
+/// `future::Retry::with_deadline`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Deadline {
+    /// The loop gives up at the given point in time.
+    At(Instant),
 
-            self.sleep.sleep(next);
+    /// The loop gives up once the given duration has passed since its first attempt.
+    After(Duration),
+}
+
+impl Deadline {
+    // Returns `None` if the deadline is too far away to be represented, i.e. never reached.
+    pub(crate) fn instant(self, start: Instant) -> Option<Instant> {
+        match self {
+            Deadline::At(instant) => Some(instant),
+            Deadline::After(duration) => start.checked_add(duration),
         }
     }
 }
 
-trait Sleep {
+impl From<Instant> for Deadline {
+    fn from(instant: Instant) -> Self {
+        Deadline::At(instant)
+    }
+}
+
+impl From<Duration> for Deadline {
+    fn from(duration: Duration) -> Self {
+        Deadline::After(duration)
+    }
+}
+
+/// Sleep waits between the attempts in [`retry_with_sleep`](fn.retry_with_sleep.html) and
+/// [`retry_notify_with_sleep`](fn.retry_notify_with_sleep.html).
+///
+/// Useful for skipping the waits in tests, or for waking up early, e.g. on shutdown.
+pub trait Sleep {
     fn sleep(&mut self, dur: Duration);
+
+    /// Same as [`sleep`](#tymethod.sleep), but should wake up early once `cancel` is cancelled,
+    /// see [`Retry::retry_cancellable`]. Returns whether `cancel` was cancelled.
+    ///
+    /// By default it sleeps for the whole `dur`, and then checks `cancel`.
+    fn sleep_cancellable(&mut self, dur: Duration, cancel: &CancelToken) -> bool {
+        self.sleep(dur);
+        cancel.is_cancelled()
+    }
 }
 
-struct ThreadSleep;
+impl<F> Sleep for F
+where
+    F: FnMut(Duration),
+{
+    fn sleep(&mut self, dur: Duration) {
+        self(dur)
+    }
+}
+
+/// Blocks the current thread with [`std::thread::sleep`]. Used by [`retry`] and [`retry_notify`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ThreadSleep;
 
 impl Sleep for ThreadSleep {
     fn sleep(&mut self, dur: Duration) {
         thread::sleep(dur);
     }
+
+    fn sleep_cancellable(&mut self, dur: Duration, cancel: &CancelToken) -> bool {
+        cancel.wait(dur)
+    }
 }
 
-/// Notify is called in [`retry_notify`](trait.Operation.html#method.retry_notify) in case of errors.
+/// Notify is called in [`retry_notify`](fn.retry_notify.html) in case of errors.
+///
+/// Implement either [`notify`](#method.notify), or [`notify_with_context`](#method.notify_with_context)
+/// if the attempt number and the elapsed time are needed as well. Implement
+/// [`on_success`](#method.on_success), [`on_give_up`](#method.on_give_up) and
+/// [`on_deadline`](#method.on_deadline) to be told how the loop ended.
+///
+/// # Example
+///
+/// ```rust
+/// use backoff::backoff::{BackoffExt, Zero};
+/// use backoff::{Notify, Retry, RetryError};
+/// use std::time::Duration;
+///
+/// struct Log;
+///
+/// impl Notify<&'static str> for Log {
+///     fn notify(&mut self, err: &'static str, _duration: Duration) {
+///         println!("retrying: {}", err);
+///     }
+///
+///     fn on_success(&mut self, attempts: u32, elapsed: Duration) {
+///         if attempts > 1 {
+///             println!("recovered after {} attempts in {:?}", attempts, elapsed);
+///         }
+///     }
+///
+///     fn on_give_up(&mut self, err: &RetryError<&'static str>) {
+///         println!("giving up: {}", err);
+///     }
+/// }
+///
+/// let mut attempts = 0;
+/// let op = || {
+///     attempts += 1;
+///     match attempts {
+///         1 => Err(backoff::Error::transient("unavailable")),
+///         _ => Ok(attempts),
+///     }
+/// };
+/// let res = Retry::new(Zero {}.with_max_retries(2))
+///     .with_notify(Log)
+///     .with_sleep(|_| ())
+///     .retry(op);
+/// assert_eq!(res.unwrap(), 2);
+/// ```
 pub trait Notify<E> {
+    /// Called with the error of the failed attempt and the duration to wait before the next one.
     fn notify(&mut self, err: E, duration: Duration);
+
+    /// Same as [`notify`](#method.notify), with additional information about the failed attempt.
+    ///
+    /// By default it calls [`notify`](#method.notify).
+    fn notify_with_context(&mut self, err: E, duration: Duration, _context: &RetryContext) {
+        self.notify(err, duration)
+    }
+
+    /// Called once the operation succeeds, with the number of attempts it took and the time
+    /// elapsed since the first one was started.
+    fn on_success(&mut self, _attempts: u32, _elapsed: Duration) {}
+
+    /// Called once the loop gives up, because of a permanent error, the exhausted backoff, the
+    /// deadline or a cancellation. The [`RetryError`] about to be returned holds the attempt
+    /// count and the elapsed time, its `history` isn't filled in yet.
+    ///
+    /// It isn't called if `future::Retry` gives up at its deadline without an error to report,
+    /// see [`on_deadline`](#method.on_deadline).
+    fn on_give_up(&mut self, _err: &RetryError<E>) {}
+
+    /// Called once `future::Retry` gives up at its deadline without an error to report: the
+    /// attempt running at the deadline got cancelled, or the error of the last attempt was
+    /// already notified and the loop was waiting for the next one. Gets the number of attempts
+    /// started and the time elapsed since the first one was started.
+    fn on_deadline(&mut self, _attempts: u32, _elapsed: Duration) {}
 }
 
 impl<E, F> Notify<E> for F
@@ -133,6 +742,17 @@ where
     }
 }
 
+/// Information about a failed attempt, passed to [`Notify::notify_with_context`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryContext {
+    /// The number of the failed attempt, starting from 1.
+    pub attempt: u32,
+    /// The time elapsed since the first attempt was started.
+    pub elapsed: Duration,
+    /// The time the failed attempt took.
+    pub attempt_duration: Duration,
+}
+
 /// No-op implementation of [`Notify`]. Literally does nothing.
 #[derive(Debug, Clone, Copy)]
 pub struct NoopNotify;
@@ -140,3 +760,43 @@ pub struct NoopNotify;
 impl<E> Notify<E> for NoopNotify {
     fn notify(&mut self, _: E, _: Duration) {}
 }
+
+#[test]
+fn retry_after_policy() {
+    use crate::backoff::{BackoffExt, Constant};
+
+    let secs = Duration::from_secs;
+    let mut backoff = Constant::new(secs(10)).with_max_retries(1);
+
+    let policy = RetryAfterPolicy::default();
+    assert_eq!(
+        policy.next(Some(secs(86400)), &mut backoff),
+        Some(secs(86400))
+    );
+    assert_eq!(policy.next(None, &mut backoff), Some(secs(10)));
+
+    let policy = RetryAfterPolicy {
+        limit: Some(secs(3600)),
+        cap: Some(secs(60)),
+        combine: Combine::Max,
+    };
+    backoff.reset();
+    assert_eq!(policy.next(Some(secs(86400)), &mut backoff), None);
+    assert_eq!(policy.next(Some(secs(120)), &mut backoff), Some(secs(60)));
+    assert_eq!(policy.next(Some(secs(1)), &mut backoff), None);
+
+    let policy = RetryAfterPolicy {
+        combine: Combine::Max,
+        ..RetryAfterPolicy::default()
+    };
+    backoff.reset();
+    assert_eq!(policy.next(Some(secs(1)), &mut backoff), Some(secs(10)));
+
+    let policy = RetryAfterPolicy {
+        combine: Combine::Count,
+        ..RetryAfterPolicy::default()
+    };
+    backoff.reset();
+    assert_eq!(policy.next(Some(secs(1)), &mut backoff), Some(secs(1)));
+    assert_eq!(policy.next(Some(secs(1)), &mut backoff), None);
+}
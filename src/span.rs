@@ -0,0 +1,117 @@
+// Spans and events of the retry loops, see the `tracing` module. Without the `tracing` feature
+// flag they are zero-sized and do nothing.
+
+#[cfg(feature = "tracing")]
+mod imp {
+    use std::fmt::Display;
+    use std::time::Duration;
+
+    use tracing_0_1::span::EnteredSpan;
+    use tracing_0_1::{error, info, warn};
+
+    use crate::error::RetryError;
+    #[cfg(feature = "futures")]
+    use crate::error::StopReason;
+
+    // The errors of the loops, recorded with their `Display` in the events.
+    pub trait Traced: Display {}
+
+    impl<E: Display + ?Sized> Traced for E {}
+
+    // A `retry` span per loop, with an `attempt` child span per attempt.
+    #[derive(Debug, Clone)]
+    pub(crate) struct Span(tracing_0_1::Span);
+
+    impl Span {
+        pub(crate) fn retry() -> Self {
+            Span(tracing_0_1::info_span!("retry"))
+        }
+
+        pub(crate) fn attempt(&self, attempt: u32) -> Self {
+            Span(tracing_0_1::info_span!(parent: &self.0, "attempt", attempt))
+        }
+
+        // Enters the span until the returned guard is dropped.
+        pub(crate) fn enter(&self) -> EnteredSpan {
+            self.0.clone().entered()
+        }
+    }
+
+    // Records the failed attempt about to be retried after `delay`.
+    pub(crate) fn failed<E: Traced>(err: &E, delay: Duration, attempt: u32) {
+        warn!(error = %err, delay = ?delay, attempt, "attempt failed, retrying");
+    }
+
+    // Records the success of the operation, if it was retried.
+    pub(crate) fn succeeded(attempts: u32, elapsed: Duration) {
+        if attempts > 1 {
+            info!(attempts, elapsed = ?elapsed, "succeeded after retries");
+        }
+    }
+
+    // Records why the loop gave up.
+    pub(crate) fn gave_up<E: Traced>(err: &RetryError<E>) {
+        error!(
+            error = %err.err,
+            reason = %err.reason,
+            attempts = err.attempts,
+            elapsed = ?err.elapsed,
+            "giving up"
+        );
+    }
+
+    // Records giving up at the deadline of `future::Retry` without an error to report.
+    #[cfg(feature = "futures")]
+    pub(crate) fn deadline(attempts: u32, elapsed: Duration) {
+        error!(
+            reason = %StopReason::Deadline,
+            attempts,
+            elapsed = ?elapsed,
+            "giving up"
+        );
+    }
+}
+
+#[cfg(not(feature = "tracing"))]
+mod imp {
+    use std::time::Duration;
+
+    use crate::error::RetryError;
+
+    // The errors of the loops, only bound to `Display` with the `tracing` feature flag.
+    pub trait Traced {}
+
+    impl<E: ?Sized> Traced for E {}
+
+    #[derive(Debug, Clone)]
+    pub(crate) struct Span;
+
+    pub(crate) struct Entered;
+
+    impl Span {
+        pub(crate) fn retry() -> Self {
+            Span
+        }
+
+        pub(crate) fn attempt(&self, _attempt: u32) -> Self {
+            Span
+        }
+
+        pub(crate) fn enter(&self) -> Entered {
+            Entered
+        }
+    }
+
+    pub(crate) fn failed<E>(_err: &E, _delay: Duration, _attempt: u32) {}
+
+    pub(crate) fn succeeded(_attempts: u32, _elapsed: Duration) {}
+
+    pub(crate) fn gave_up<E>(_err: &RetryError<E>) {}
+
+    #[cfg(feature = "futures")]
+    pub(crate) fn deadline(_attempts: u32, _elapsed: Duration) {}
+}
+
+#[cfg(feature = "futures")]
+pub(crate) use imp::deadline;
+pub(crate) use imp::{failed, gave_up, succeeded, Span, Traced};
@@ -0,0 +1,132 @@
+//! Virtual time for testing retry logic.
+//!
+//! [`MockClock`] only moves forward when it is advanced, and [`MockSleeper`] advances it
+//! instead of waiting. Using them for both the backoff policy and the retry loop runs a
+//! whole retry schedule instantly:
+//!
+//! ```rust
+//! use backoff::testing::{MockClock, MockSleeper};
+//! use backoff::{Error, ExponentialBackoffBuilder, Retry};
+//! use std::time::Duration;
+//!
+//! let clock = MockClock::new();
+//! let sleeper = MockSleeper::new(clock.clone());
+//! let backoff = ExponentialBackoffBuilder::new()
+//!     .with_max_elapsed_time(Some(Duration::from_secs(60)))
+//!     .with_clock(clock.clone())
+//!     .with_seed(42)
+//!     .build();
+//!
+//! let f = || -> Result<(), Error<&str>> { Err(Error::transient("error")) };
+//! let res = Retry::new(backoff)
+//!     .with_sleep(sleeper.clone())
+//!     .with_clock(clock.clone())
+//!     .retry(f);
+//!
+//! assert_eq!(res, Err(Error::transient("error")));
+//! assert_eq!(clock.elapsed(), sleeper.sleeps().iter().sum());
+//! assert!(clock.elapsed() <= Duration::from_secs(60));
+//! ```
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use instant::Instant;
+
+use crate::clock::Clock;
+use crate::retry::Sleep;
+
+/// MockClock is a [`Clock`] which only moves when it is advanced.
+///
+/// Clones share the same time, so a clone can be given to the backoff policy and to
+/// the retry loop, and advanced from the test.
+#[derive(Debug, Clone)]
+pub struct MockClock {
+    start: Instant,
+    now: Arc<Mutex<Instant>>,
+}
+
+impl MockClock {
+    /// Creates a new MockClock, stopped at the current time.
+    pub fn new() -> MockClock {
+        let start = Instant::now();
+        MockClock {
+            start,
+            now: Arc::new(Mutex::new(start)),
+        }
+    }
+
+    /// Moves the clock forward by `dur`.
+    pub fn advance(&self, dur: Duration) {
+        *self.now.lock().unwrap() += dur;
+    }
+
+    /// Returns how far the clock has been advanced since it was created.
+    pub fn elapsed(&self) -> Duration {
+        self.now().duration_since(self.start)
+    }
+}
+
+impl Default for MockClock {
+    fn default() -> MockClock {
+        MockClock::new()
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> Instant {
+        *self.now.lock().unwrap()
+    }
+}
+
+/// MockSleeper advances a [`MockClock`] instead of waiting, and records the durations
+/// it was asked to wait for.
+///
+/// It implements both [`Sleep`] for the blocking retry loop, and
+/// `future::Sleeper` for the async one if the `futures` feature is enabled.
+/// Clones share the same clock and records.
+#[derive(Debug, Clone)]
+pub struct MockSleeper {
+    clock: MockClock,
+    sleeps: Arc<Mutex<Vec<Duration>>>,
+}
+
+impl MockSleeper {
+    /// Creates a new MockSleeper which advances `clock`.
+    pub fn new(clock: MockClock) -> MockSleeper {
+        MockSleeper {
+            clock,
+            sleeps: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Returns the clock advanced by this sleeper.
+    pub fn clock(&self) -> &MockClock {
+        &self.clock
+    }
+
+    /// Returns the durations this sleeper was asked to wait for, in order.
+    pub fn sleeps(&self) -> Vec<Duration> {
+        self.sleeps.lock().unwrap().clone()
+    }
+
+    fn advance(&self, dur: Duration) {
+        self.sleeps.lock().unwrap().push(dur);
+        self.clock.advance(dur);
+    }
+}
+
+impl Sleep for MockSleeper {
+    fn sleep(&mut self, dur: Duration) {
+        self.advance(dur);
+    }
+}
+
+#[cfg(feature = "futures")]
+impl crate::future::Sleeper for MockSleeper {
+    type Sleep = std::future::Ready<()>;
+    fn sleep(&self, dur: Duration) -> Self::Sleep {
+        self.advance(dur);
+        std::future::ready(())
+    }
+}
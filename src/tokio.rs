@@ -0,0 +1,55 @@
+//! Retrying futures on the [tokio](https://crates.io/crates/tokio) runtime.
+
+use std::future::Future;
+use std::time::Duration;
+
+use crate::backoff::Backoff;
+use crate::error::Error;
+use crate::future::{retry_notify_with_sleeper, Retry, Sleeper};
+use crate::retry::{NoopNotify, Notify};
+
+/// [`Sleeper`] backed by [`tokio::time::sleep`](https://docs.rs/tokio/1/tokio/time/fn.sleep.html).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TokioSleeper;
+
+impl Sleeper for TokioSleeper {
+    type Sleep = ::tokio_1::time::Sleep;
+    fn sleep(&self, dur: Duration) -> Self::Sleep {
+        ::tokio_1::time::sleep(dur)
+    }
+}
+
+/// Retries given `operation` according to the [`Backoff`] policy on the tokio runtime.
+/// [`Backoff`] is reset before it is used.
+///
+/// See [`crate::future::retry`].
+pub fn retry<I, E, Fn, Fut, B>(
+    backoff: B,
+    operation: Fn,
+) -> Retry<TokioSleeper, B, NoopNotify, Fn, Fut>
+where
+    B: Backoff,
+    Fn: FnMut() -> Fut,
+    Fut: Future<Output = Result<I, Error<E>>>,
+{
+    retry_notify(backoff, operation, NoopNotify)
+}
+
+/// Retries given `operation` according to the [`Backoff`] policy on the tokio runtime.
+/// Calls `notify` on failed attempts (in case of [`Error::Transient`]).
+/// [`Backoff`] is reset before it is used.
+///
+/// See [`crate::future::retry_notify`].
+pub fn retry_notify<I, E, Fn, Fut, B, N>(
+    backoff: B,
+    operation: Fn,
+    notify: N,
+) -> Retry<TokioSleeper, B, N, Fn, Fut>
+where
+    B: Backoff,
+    Fn: FnMut() -> Fut,
+    Fut: Future<Output = Result<I, Error<E>>>,
+    N: Notify<E>,
+{
+    retry_notify_with_sleeper(TokioSleeper, backoff, operation, notify)
+}
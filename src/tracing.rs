@@ -0,0 +1,25 @@
+//! Instrumenting the retry loops with [tracing](https://crates.io/crates/tracing).
+//!
+//! With the `tracing` feature flag, the loops of [`retry_notify`](../fn.retry_notify.html),
+//! [`Retry`](../struct.Retry.html) and `future::Retry` open an `INFO` span named `retry`,
+//! with a child span named `attempt` per attempt, holding its number. In these spans they
+//! record a `WARN` event per failed attempt, with the error, the delay before the next attempt
+//! and the attempt number, an `INFO` event when the operation succeeds after retries, and an
+//! `ERROR` event when they give up.
+//!
+//! The events record the errors with their `Display` implementation, so the feature flag
+//! requires the errors of the loops to implement [`Display`](std::fmt::Display).
+//!
+//! # Example
+//!
+//! ```rust
+//! use backoff::ExponentialBackoff;
+//!
+//! fn f() -> Result<(), backoff::Error<std::io::Error>> {
+//!     // Business logic...
+//!     Ok(())
+//! }
+//!
+//! // Records the events of the failed attempts, without passing a `Notify`.
+//! backoff::retry(ExponentialBackoff::default(), f).unwrap();
+//! ```
@@ -2,18 +2,20 @@ extern crate backoff;
 extern crate instant;
 
 use backoff::backoff::Backoff;
-use backoff::exponential::ExponentialBackoff;
+use backoff::exponential::{ExponentialBackoff, ExponentialBackoffBuilder};
 use backoff::{Clock, SystemClock};
 
 use instant::Instant;
 use std::cell::RefCell;
 use std::time::Duration;
 
+#[derive(Clone)]
 struct Inner {
     i: Duration,
     start: Instant,
 }
 
+#[derive(Clone)]
 struct TestClock(RefCell<Inner>);
 
 impl TestClock {
@@ -96,3 +98,27 @@ fn backoff() {
         exp.next_backoff();
     }
 }
+
+#[test]
+fn seeded_backoff_is_reproducible() {
+    let builder = ExponentialBackoffBuilder::<SystemClock>::new()
+        .with_max_elapsed_time(None)
+        .with_seed(42);
+    let mut first = builder.build();
+    let mut second = builder.build();
+
+    for _ in 0..10 {
+        assert_eq!(first.next_backoff(), second.next_backoff());
+    }
+}
+
+#[test]
+fn builder_with_clock() {
+    let start = Instant::now();
+    let exp = ExponentialBackoffBuilder::<SystemClock>::new()
+        .with_clock(TestClock::new(Duration::from_secs(5), start))
+        .build();
+
+    assert_eq!(exp.start_time, start + Duration::from_secs(5));
+    assert_eq!(exp.get_elapsed_time(), Duration::from_secs(1));
+}
@@ -0,0 +1,409 @@
+#![cfg(all(feature = "futures", feature = "testing"))]
+
+use backoff::backoff::{BackoffExt, Constant};
+use backoff::future::retry_notify_with_sleeper;
+use backoff::testing::{MockClock, MockSleeper};
+use backoff::Error;
+
+use std::future::ready;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[test]
+fn retry_with_sleeper() {
+    let sleeper = MockSleeper::new(MockClock::new());
+    let mut attempts = 0;
+    let mut notified = Vec::new();
+
+    let backoff = Constant::new(Duration::from_secs(1)).with_max_retries(5);
+    let res = futures_executor::block_on(retry_notify_with_sleeper(
+        sleeper.clone(),
+        backoff,
+        || {
+            attempts += 1;
+            let res = if attempts == 3 {
+                Ok(attempts)
+            } else {
+                Err(Error::transient("err"))
+            };
+            ready(res)
+        },
+        |_, dur| notified.push(dur),
+    ));
+
+    assert_eq!(res, Ok(3));
+    assert_eq!(notified, vec![Duration::from_secs(1); 2]);
+    assert_eq!(sleeper.sleeps(), notified);
+}
+
+#[cfg(feature = "tokio")]
+#[test]
+fn tokio_retry() {
+    let rt = tokio_1::runtime::Runtime::new().unwrap();
+    let mut attempts = 0;
+    let res = rt.block_on(backoff::tokio::retry(
+        backoff::backoff::Zero {}.with_max_retries(1),
+        || {
+            attempts += 1;
+            ready(Err::<(), _>(Error::transient("err")))
+        },
+    ));
+
+    assert_eq!(res, Err("err"));
+    assert_eq!(attempts, 2);
+}
+
+#[cfg(feature = "async-std")]
+#[test]
+fn async_std_retry() {
+    let mut attempts = 0;
+    let res = async_std_1::task::block_on(backoff::async_std::retry(
+        backoff::backoff::Zero {}.with_max_retries(1),
+        || {
+            attempts += 1;
+            ready(Err::<(), _>(Error::transient("err")))
+        },
+    ));
+
+    assert_eq!(res, Err("err"));
+    assert_eq!(attempts, 2);
+}
+
+#[test]
+fn timeout() {
+    use backoff::future::timeout;
+    use backoff::TimeoutError;
+    use std::future::{pending, Future};
+    use std::pin::Pin;
+
+    type Attempt = Pin<Box<dyn Future<Output = Result<u32, Error<&'static str>>>>>;
+
+    let mut attempts = 0;
+    let mut notified = Vec::new();
+    let operation = || -> Attempt {
+        attempts += 1;
+        match attempts {
+            1 => Box::pin(pending()),
+            2 => Box::pin(ready(Err(Error::transient("err")))),
+            _ => Box::pin(ready(Ok(attempts))),
+        }
+    };
+
+    let sleeper = MockSleeper::new(MockClock::new());
+    let backoff = Constant::new(Duration::from_secs(1)).with_max_retries(5);
+    let res = futures_executor::block_on(retry_notify_with_sleeper(
+        sleeper.clone(),
+        backoff,
+        timeout(sleeper, Duration::from_secs(2), operation),
+        |err, _| notified.push(err),
+    ));
+
+    assert_eq!(res, Ok(3));
+    assert_eq!(
+        notified,
+        vec![
+            TimeoutError::TimedOut(Duration::from_secs(2)),
+            TimeoutError::Operation("err"),
+        ]
+    );
+}
+
+#[cfg(feature = "tokio")]
+#[test]
+fn deadline() {
+    use backoff::DeadlineError;
+    use std::future::{pending, Future};
+    use std::pin::Pin;
+    use std::time::Instant;
+
+    type Attempt = Pin<Box<dyn Future<Output = Result<(), Error<&'static str>>> + Send>>;
+
+    let rt = tokio_1::runtime::Runtime::new().unwrap();
+    let mut attempts = 0;
+    let operation = || -> Attempt {
+        attempts += 1;
+        match attempts {
+            1 => Box::pin(ready(Err(Error::transient("err")))),
+            _ => Box::pin(pending()),
+        }
+    };
+
+    let start = Instant::now();
+    let res = rt.block_on(
+        backoff::tokio::retry(Constant::new(Duration::from_millis(1)), operation)
+            .with_deadline(Duration::from_millis(50)),
+    );
+
+    assert_eq!(res, Err(DeadlineError::Exceeded(None)));
+    assert!(start.elapsed() < Duration::from_secs(10));
+    assert_eq!(attempts, 2);
+}
+
+#[cfg(feature = "tokio")]
+#[test]
+fn cancel() {
+    use backoff::CancelError;
+    use std::future::pending;
+    use std::time::Instant;
+
+    let rt = tokio_1::runtime::Runtime::new().unwrap();
+    let _guard = rt.enter();
+    let mut attempts = 0;
+    let start = Instant::now();
+    let res = rt.block_on(
+        backoff::tokio::retry(Constant::new(Duration::from_secs(60)), || {
+            attempts += 1;
+            ready(Err::<(), _>(Error::transient("err")))
+        })
+        .with_cancel(tokio_1::time::sleep(Duration::from_millis(20))),
+    );
+
+    assert_eq!(res, Err(CancelError::Cancelled("err")));
+    assert!(start.elapsed() < Duration::from_secs(10));
+    assert_eq!(attempts, 2);
+
+    let res = rt.block_on(
+        backoff::tokio::retry(backoff::backoff::Zero {}.with_max_retries(1), || {
+            ready(Err::<(), _>(Error::transient("err")))
+        })
+        .with_cancel(pending()),
+    );
+    assert_eq!(res, Err(CancelError::Failed("err")));
+}
+
+#[test]
+fn report() {
+    use backoff::StopReason;
+
+    let mut attempts = 0;
+    let report = futures_executor::block_on(
+        retry_notify_with_sleeper(
+            MockSleeper::new(MockClock::new()),
+            Constant::new(Duration::from_secs(1)).with_max_retries(2),
+            || {
+                attempts += 1;
+                ready(Err::<(), _>(Error::transient(attempts)))
+            },
+            |_, _| (),
+        )
+        .report_with_history(),
+    )
+    .unwrap_err();
+
+    assert_eq!(report.err, 3);
+    assert_eq!(report.reason, StopReason::Exhausted);
+    assert_eq!(report.attempts, 3);
+    assert_eq!(report.history, vec![1, 2]);
+}
+
+#[test]
+fn classified() {
+    use backoff::classify::Decision;
+    use backoff::future::{classified, retry_with_sleeper};
+
+    let sleeper = MockSleeper::new(MockClock::new());
+    let mut attempts = 0;
+    let operation = || {
+        attempts += 1;
+        ready(match attempts {
+            1 => Err("rate limited"),
+            2 => Err("unavailable"),
+            _ => Ok(attempts),
+        })
+    };
+    let classify = |err: &&str| match *err {
+        "rate limited" => Decision::RetryAfter(Duration::from_secs(5)),
+        _ => Decision::Transient,
+    };
+
+    let res = futures_executor::block_on(retry_with_sleeper(
+        sleeper.clone(),
+        Constant::new(Duration::from_secs(1)),
+        classified(classify, operation),
+    ));
+
+    assert_eq!(res, Ok(3));
+    assert_eq!(
+        sleeper.sleeps(),
+        vec![Duration::from_secs(5), Duration::from_secs(1)]
+    );
+}
+
+#[test]
+fn retry_after_policy() {
+    use backoff::{Combine, RetryAfterPolicy};
+
+    let sleeper = MockSleeper::new(MockClock::new());
+    let policy = RetryAfterPolicy {
+        cap: Some(Duration::from_secs(60)),
+        combine: Combine::Count,
+        ..RetryAfterPolicy::default()
+    };
+    let res = futures_executor::block_on(
+        backoff::future::retry_with_sleeper(
+            sleeper.clone(),
+            Constant::new(Duration::from_secs(1)).with_max_retries(2),
+            || {
+                ready(Err::<(), _>(Error::retry_after(
+                    "err",
+                    Duration::from_secs(86400),
+                )))
+            },
+        )
+        .with_retry_after(policy),
+    );
+
+    assert_eq!(res, Err("err"));
+    assert_eq!(sleeper.sleeps(), vec![Duration::from_secs(60); 2]);
+}
+
+#[test]
+fn async_notify() {
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    // Pending on the first poll, like a write to a slow audit log.
+    struct YieldOnce(bool);
+
+    impl Future for YieldOnce {
+        type Output = ();
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+            if self.0 {
+                return Poll::Ready(());
+            }
+            self.0 = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+
+    let sleeper = MockSleeper::new(MockClock::new());
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let notify = {
+        let (sleeper, log) = (sleeper.clone(), log.clone());
+        move |err: &&'static str, _| {
+            let (sleeper, log, err) = (sleeper.clone(), log.clone(), *err);
+            async move {
+                let sleeps = sleeper.sleeps().len();
+                YieldOnce(false).await;
+                // The delay starts once the notification completes.
+                assert_eq!(sleeper.sleeps().len(), sleeps);
+                log.lock().unwrap().push(format!("async {}", err));
+            }
+        }
+    };
+    let sync_log = log.clone();
+
+    let mut attempts = 0;
+    let res = futures_executor::block_on(
+        backoff::future::retry_notify_with_sleeper(
+            sleeper.clone(),
+            Constant::new(Duration::from_secs(1)),
+            || {
+                attempts += 1;
+                ready(match attempts {
+                    1 => Err(Error::transient("first")),
+                    2 => Err(Error::transient("second")),
+                    _ => Ok(attempts),
+                })
+            },
+            move |err, _| sync_log.lock().unwrap().push(format!("sync {}", err)),
+        )
+        .with_async_notify(notify),
+    );
+
+    assert_eq!(res, Ok(3));
+    assert_eq!(
+        *log.lock().unwrap(),
+        vec!["sync first", "async first", "sync second", "async second"]
+    );
+    assert_eq!(sleeper.sleeps(), vec![Duration::from_secs(1); 2]);
+}
+
+#[test]
+fn async_classifier() {
+    use backoff::classify::Decision;
+    use backoff::future::retry_with_sleeper;
+
+    let sleeper = MockSleeper::new(MockClock::new());
+    let mut attempts = 0;
+    let operation = || {
+        attempts += 1;
+        ready(Err::<(), _>(match attempts {
+            1 => Error::retry_after("throttled", Duration::from_secs(5)),
+            2 => Error::transient("unauthorized"),
+            _ => Error::transient("revoked"),
+        }))
+    };
+    let classify = |err: &&str| {
+        let decision = match *err {
+            "unauthorized" => Decision::RetryAfter(Duration::ZERO),
+            "revoked" => Decision::Permanent,
+            _ => Decision::Transient,
+        };
+        async move { decision }
+    };
+
+    let res = futures_executor::block_on(
+        retry_with_sleeper(
+            sleeper.clone(),
+            Constant::new(Duration::from_secs(1)),
+            operation,
+        )
+        .with_async_classifier(classify),
+    );
+
+    assert_eq!(res, Err("revoked"));
+    assert_eq!(
+        sleeper.sleeps(),
+        vec![Duration::from_secs(5), Duration::ZERO]
+    );
+}
+
+#[test]
+fn success_and_give_up_hooks() {
+    use backoff::{Notify, RetryError, StopReason};
+
+    #[derive(Default)]
+    struct Outcome(Vec<(StopReason, u32)>, Option<u32>);
+
+    impl Notify<&'static str> for &mut Outcome {
+        fn notify(&mut self, _: &'static str, _: Duration) {}
+
+        fn on_success(&mut self, attempts: u32, _: Duration) {
+            self.1 = Some(attempts);
+        }
+
+        fn on_give_up(&mut self, err: &RetryError<&'static str>) {
+            self.0.push((err.reason, err.attempts));
+        }
+    }
+
+    let mut outcome = Outcome::default();
+    let mut attempts = 0;
+    let res = futures_executor::block_on(retry_notify_with_sleeper(
+        MockSleeper::new(MockClock::new()),
+        Constant::new(Duration::from_secs(1)),
+        || {
+            attempts += 1;
+            ready(if attempts < 3 {
+                Err(Error::transient("err"))
+            } else {
+                Ok(())
+            })
+        },
+        &mut outcome,
+    ));
+    assert_eq!(res, Ok(()));
+    assert_eq!(outcome.1, Some(3));
+
+    let res = futures_executor::block_on(retry_notify_with_sleeper(
+        MockSleeper::new(MockClock::new()),
+        Constant::new(Duration::from_secs(1)),
+        || ready(Err::<(), _>(Error::permanent("err"))),
+        &mut outcome,
+    ));
+    assert_eq!(res, Err("err"));
+    assert_eq!(outcome.0, vec![(StopReason::Permanent, 1)]);
+}
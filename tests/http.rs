@@ -0,0 +1,108 @@
+#![cfg(feature = "http")]
+
+use backoff::backoff::Constant;
+use backoff::classify::Classify;
+use backoff::{retry_notify_with_sleep, Error};
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+// Answers the requests to the returned URL with `responses`, one per connection.
+fn serve(responses: Vec<&'static str>) -> (String, JoinHandle<()>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/", listener.local_addr().unwrap());
+
+    let handle = thread::spawn(move || {
+        for response in responses {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut line = String::new();
+            while reader.read_line(&mut line).unwrap() > 2 {
+                line.clear();
+            }
+            reader.get_mut().write_all(response.as_bytes()).unwrap();
+        }
+    });
+
+    (url, handle)
+}
+
+fn fetch(url: &str) -> Result<String, Error<reqwest::Error>> {
+    let resp = reqwest::blocking::get(url)?;
+    let decision = backoff::http::classify(resp.status(), resp.headers());
+    match resp.error_for_status() {
+        Ok(resp) => Ok(resp.text()?),
+        Err(err) => Err(decision.apply(err)),
+    }
+}
+
+#[test]
+fn retry_after_header() {
+    let (url, server) = serve(vec![
+        "HTTP/1.1 429 Too Many Requests\r\nRetry-After: 120\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+        "HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+        "HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok",
+    ]);
+
+    let mut sleeps = Vec::new();
+    let res = retry_notify_with_sleep(
+        Constant::new(Duration::from_secs(1)),
+        || fetch(&url),
+        |_, _| (),
+        |dur| sleeps.push(dur),
+    );
+    server.join().unwrap();
+
+    assert_eq!(res.unwrap(), "ok");
+    assert_eq!(
+        sleeps,
+        vec![Duration::from_secs(120), Duration::from_secs(1)]
+    );
+}
+
+#[test]
+fn client_error_is_permanent() {
+    let (url, server) = serve(vec![
+        "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+    ]);
+
+    let res = retry_notify_with_sleep(
+        Constant::new(Duration::from_secs(1)),
+        || fetch(&url),
+        |_, _| (),
+        |_| panic!("a permanent error isn't retried"),
+    );
+    server.join().unwrap();
+
+    match res {
+        Err(Error::Permanent(err)) => {
+            assert_eq!(err.status(), Some(reqwest::StatusCode::NOT_FOUND))
+        }
+        _ => panic!("expected a permanent error"),
+    }
+}
+
+#[test]
+fn status_classifier() {
+    use backoff::classify::Decision;
+    use http_0_2::{HeaderMap, StatusCode};
+
+    let classify = |err: &StatusCode| backoff::http::classify(*err, &HeaderMap::new());
+    for status in &[
+        StatusCode::TOO_MANY_REQUESTS,
+        StatusCode::BAD_GATEWAY,
+        StatusCode::SERVICE_UNAVAILABLE,
+        StatusCode::GATEWAY_TIMEOUT,
+    ] {
+        assert_eq!(classify.classify(status), Decision::Transient);
+    }
+    for status in &[
+        StatusCode::BAD_REQUEST,
+        StatusCode::UNAUTHORIZED,
+        StatusCode::NOT_FOUND,
+    ] {
+        assert_eq!(classify.classify(status), Decision::Permanent);
+    }
+}
@@ -0,0 +1,59 @@
+#![cfg(feature = "log")]
+
+use backoff::backoff::{BackoffExt, Constant};
+use backoff::log::LogNotify;
+use backoff::Error;
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use log_0_4::{Level, LevelFilter, Log, Metadata, Record};
+
+// Keeps the log records as `LEVEL target: message` lines.
+struct Recorder(Mutex<Vec<String>>);
+
+impl Log for Recorder {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn log(&self, record: &Record<'_>) {
+        let line = format!("{} {}: {}", record.level(), record.target(), record.args());
+        self.0.lock().unwrap().push(line);
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: Recorder = Recorder(Mutex::new(Vec::new()));
+
+#[test]
+fn log_notify() {
+    log_0_4::set_logger(&LOGGER).unwrap();
+    log_0_4::set_max_level(LevelFilter::Trace);
+
+    let backoff = Constant::new(Duration::from_millis(1)).with_max_retries(2);
+    let f = || -> Result<(), Error<&str>> { Err(Error::transient("unavailable")) };
+    let _ = backoff::retry_notify(backoff, f, LogNotify::default());
+
+    let backoff = Constant::new(Duration::from_millis(1)).with_max_retries(1);
+    let notify = LogNotify {
+        level: Level::Info,
+        target: "sync",
+        prefix: "fetching failed",
+    };
+    let _ = backoff::retry_notify(backoff, f, notify);
+
+    assert_eq!(
+        *LOGGER.0.lock().unwrap(),
+        vec![
+            "WARN backoff: transient error: unavailable, retrying in 1ms (attempt 1)",
+            "WARN backoff: transient error: unavailable, retrying in 1ms (attempt 2)",
+            "WARN backoff: transient error: unavailable, giving up after 3 attempt(s) \
+             (retries exhausted)",
+            "INFO sync: fetching failed: unavailable, retrying in 1ms (attempt 1)",
+            "INFO sync: fetching failed: unavailable, giving up after 2 attempt(s) \
+             (retries exhausted)",
+        ]
+    );
+}
@@ -1,9 +1,14 @@
 extern crate backoff;
 
+use backoff::backoff::{BackoffExt, Zero};
 use backoff::Error;
 use backoff::ExponentialBackoff;
+use backoff::{Notify, RetryContext};
 
+use std::cell::RefCell;
 use std::io;
+use std::rc::Rc;
+use std::time::Duration;
 
 #[test]
 fn retry() {
@@ -45,3 +50,181 @@ fn permanent_error_immediately_returned() {
         other => panic!("{}", other),
     }
 }
+
+#[test]
+fn max_retries() {
+    let mut i = 0;
+
+    {
+        let f = || -> Result<(), Error<&str>> {
+            i += 1;
+            Err(Error::transient("err"))
+        };
+
+        let backoff = Zero {}.with_max_retries(3);
+        assert_eq!(
+            backoff::retry(backoff, f).err().unwrap(),
+            Error::transient("err")
+        );
+    }
+
+    assert_eq!(i, 4);
+}
+
+#[test]
+fn notify_with_context() {
+    struct Attempts(Rc<RefCell<Vec<u32>>>);
+
+    impl Notify<&'static str> for Attempts {
+        fn notify(&mut self, _: &'static str, _: Duration) {}
+
+        fn notify_with_context(&mut self, _: &'static str, _: Duration, context: &RetryContext) {
+            assert!(context.attempt_duration <= context.elapsed);
+            self.0.borrow_mut().push(context.attempt);
+        }
+    }
+
+    let attempts = Rc::new(RefCell::new(Vec::new()));
+    let f = || -> Result<(), Error<&str>> { Err(Error::transient("err")) };
+
+    let notify = Attempts(attempts.clone());
+    let _ = backoff::retry_notify(Zero {}.with_max_retries(3), f, notify);
+    assert_eq!(*attempts.borrow(), vec![1, 2, 3]);
+}
+
+#[test]
+fn retry_cancellable() {
+    use backoff::backoff::Constant;
+    use backoff::{CancelError, CancelToken, Retry};
+    use std::thread;
+    use std::time::Instant;
+
+    let cancel = CancelToken::new();
+    let shutdown = cancel.clone();
+    let handle = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(20));
+        shutdown.cancel();
+    });
+
+    let mut attempts = 0;
+    let f = || -> Result<(), Error<&str>> {
+        attempts += 1;
+        Err(Error::transient("err"))
+    };
+
+    let start = Instant::now();
+    let res = Retry::new(Constant::new(Duration::from_secs(60))).retry_cancellable(f, &cancel);
+    handle.join().unwrap();
+
+    assert_eq!(res, Err(CancelError::Cancelled(Error::transient("err"))));
+    assert_eq!(attempts, 1);
+    assert!(start.elapsed() < Duration::from_secs(10));
+
+    let res = Retry::new(Zero {}.with_max_retries(1)).retry_cancellable(
+        || Err::<(), _>(Error::transient("err")),
+        &CancelToken::new(),
+    );
+    assert_eq!(res, Err(CancelError::Failed(Error::transient("err"))));
+}
+
+#[test]
+fn retry_report() {
+    use backoff::{Retry, StopReason};
+
+    let mut attempts = 0;
+    let f = || -> Result<(), Error<u32>> {
+        attempts += 1;
+        if attempts == 3 {
+            Err(Error::permanent(attempts))
+        } else {
+            Err(Error::transient(attempts))
+        }
+    };
+
+    let report = Retry::new(Zero {})
+        .retry_report_with_history(f)
+        .unwrap_err();
+    assert_eq!(report.err, 3);
+    assert_eq!(report.reason, StopReason::Permanent);
+    assert_eq!(report.attempts, 3);
+    assert_eq!(report.history, vec![1, 2]);
+
+    let report = Retry::new(Zero {}.with_max_retries(1))
+        .retry_report(|| Err::<(), _>(Error::transient("err")))
+        .unwrap_err();
+    assert_eq!(report.reason, StopReason::Exhausted);
+    assert_eq!(report.attempts, 2);
+    assert!(report.history.is_empty());
+}
+
+#[test]
+fn retry_if() {
+    use backoff::classify::{Classify, Decision};
+
+    struct NotFoundIsPermanent;
+
+    impl Classify<io::Error> for NotFoundIsPermanent {
+        fn classify(&self, err: &io::Error) -> Decision {
+            match err.kind() {
+                io::ErrorKind::NotFound => Decision::Permanent,
+                _ => Decision::Transient,
+            }
+        }
+    }
+
+    let mut attempts = 0;
+    let f = || -> Result<(), io::Error> {
+        attempts += 1;
+        match attempts {
+            1 => Err(io::ErrorKind::TimedOut.into()),
+            _ => Err(io::ErrorKind::NotFound.into()),
+        }
+    };
+
+    let err = backoff::retry_if(Zero {}, f, NotFoundIsPermanent).unwrap_err();
+    match err {
+        Error::Permanent(err) => assert_eq!(err.kind(), io::ErrorKind::NotFound),
+        _ => panic!("expected a permanent error"),
+    }
+    assert_eq!(attempts, 2);
+}
+
+#[test]
+fn success_and_give_up_hooks() {
+    use backoff::{RetryError, StopReason};
+
+    #[derive(Default)]
+    struct Outcome(Rc<RefCell<Vec<(&'static str, u32)>>>);
+
+    impl Notify<&'static str> for Outcome {
+        fn notify(&mut self, _: &'static str, _: Duration) {}
+
+        fn on_success(&mut self, attempts: u32, _: Duration) {
+            self.0.borrow_mut().push(("success", attempts));
+        }
+
+        fn on_give_up(&mut self, err: &RetryError<&'static str>) {
+            assert_eq!(err.reason, StopReason::Exhausted);
+            self.0.borrow_mut().push((err.err, err.attempts));
+        }
+    }
+
+    let outcome = Outcome::default();
+    let mut attempts = 0;
+    let f = || -> Result<u32, Error<&str>> {
+        attempts += 1;
+        if attempts < 3 {
+            Err(Error::transient("err"))
+        } else {
+            Ok(attempts)
+        }
+    };
+    let notify = Outcome(outcome.0.clone());
+    assert_eq!(backoff::retry_notify(Zero {}, f, notify).unwrap(), 3);
+
+    let f = || -> Result<(), Error<&str>> { Err(Error::transient("err")) };
+    let notify = Outcome(outcome.0.clone());
+    let _ = backoff::retry_notify(Zero {}.with_max_retries(1), f, notify);
+
+    assert_eq!(*outcome.0.borrow(), vec![("success", 3), ("err", 2)]);
+}
@@ -0,0 +1,356 @@
+#![cfg(feature = "testing")]
+
+use backoff::backoff::{BackoffExt, Constant};
+use backoff::testing::{MockClock, MockSleeper};
+use backoff::{Error, Notify, Retry, RetryContext, StopReason};
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[derive(Clone, Default)]
+struct Contexts(Arc<Mutex<Vec<RetryContext>>>);
+
+impl<E> Notify<E> for Contexts {
+    fn notify(&mut self, _: E, _: Duration) {}
+
+    fn notify_with_context(&mut self, _: E, _: Duration, context: &RetryContext) {
+        self.0.lock().unwrap().push(*context);
+    }
+}
+
+fn expected_contexts() -> Vec<RetryContext> {
+    (0..3)
+        .map(|i| RetryContext {
+            attempt: i + 1,
+            elapsed: Duration::from_secs(u64::from(i)),
+            attempt_duration: Duration::from_secs(0),
+        })
+        .collect()
+}
+
+#[test]
+fn retry_in_virtual_time() {
+    let clock = MockClock::new();
+    let sleeper = MockSleeper::new(clock.clone());
+    let contexts = Contexts::default();
+
+    let f = || -> Result<(), Error<&str>> { Err(Error::transient("err")) };
+    let res = Retry::new(Constant::new(Duration::from_secs(1)).with_max_retries(3))
+        .with_notify(contexts.clone())
+        .with_sleep(sleeper.clone())
+        .with_clock(clock.clone())
+        .retry(f);
+
+    assert_eq!(res, Err(Error::transient("err")));
+    assert_eq!(sleeper.sleeps(), vec![Duration::from_secs(1); 3]);
+    assert_eq!(clock.elapsed(), Duration::from_secs(3));
+    assert_eq!(*contexts.0.lock().unwrap(), expected_contexts());
+}
+
+#[cfg(feature = "futures")]
+#[test]
+fn future_retry_in_virtual_time() {
+    use backoff::future::retry_notify_with_sleeper;
+    use std::future::ready;
+
+    let clock = MockClock::new();
+    let sleeper = MockSleeper::new(clock.clone());
+    let contexts = Contexts::default();
+
+    let backoff = Constant::new(Duration::from_secs(1)).with_max_retries(3);
+    let retry = retry_notify_with_sleeper(
+        sleeper.clone(),
+        backoff,
+        || ready(Err::<(), _>(Error::transient("err"))),
+        contexts.clone(),
+    )
+    .with_clock(clock.clone());
+
+    assert_eq!(futures_executor::block_on(retry), Err("err"));
+    assert_eq!(sleeper.sleeps(), vec![Duration::from_secs(1); 3]);
+    assert_eq!(clock.elapsed(), Duration::from_secs(3));
+    assert_eq!(*contexts.0.lock().unwrap(), expected_contexts());
+}
+
+#[cfg(feature = "futures")]
+#[test]
+fn future_async_classifier_in_virtual_time() {
+    use backoff::classify::Decision;
+    use backoff::future::retry_notify_with_sleeper;
+    use std::future::ready;
+
+    let clock = MockClock::new();
+    let sleeper = MockSleeper::new(clock.clone());
+    let contexts = Contexts::default();
+
+    // Deciding takes a second, which doesn't count toward the attempts.
+    let classify = |_: &&str| {
+        let clock = clock.clone();
+        async move {
+            clock.advance(Duration::from_secs(1));
+            Decision::Transient
+        }
+    };
+    let backoff = Constant::new(Duration::from_secs(1)).with_max_retries(3);
+    let retry = retry_notify_with_sleeper(
+        sleeper.clone(),
+        backoff,
+        || ready(Err::<(), _>(Error::transient("err"))),
+        contexts.clone(),
+    )
+    .with_clock(clock.clone())
+    .with_async_classifier(classify);
+
+    assert_eq!(futures_executor::block_on(retry), Err("err"));
+    assert_eq!(sleeper.sleeps(), vec![Duration::from_secs(1); 3]);
+    assert_eq!(clock.elapsed(), Duration::from_secs(7));
+    let elapsed: Vec<_> = contexts
+        .0
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|c| c.elapsed)
+        .collect();
+    assert_eq!(elapsed, [0, 2, 4].map(Duration::from_secs));
+    assert!(contexts
+        .0
+        .lock()
+        .unwrap()
+        .iter()
+        .all(|c| c.attempt_duration.is_zero()));
+}
+
+#[test]
+fn cancel_in_virtual_time() {
+    use backoff::{CancelError, CancelToken, Sleep};
+
+    let clock = MockClock::new();
+    let sleeper = MockSleeper::new(clock.clone());
+    let cancel = CancelToken::new();
+
+    // Shuts down during the second wait.
+    let sleep = {
+        let (mut sleeper, cancel) = (sleeper.clone(), cancel.clone());
+        move |dur| {
+            sleeper.sleep(dur);
+            if sleeper.sleeps().len() == 2 {
+                cancel.cancel();
+            }
+        }
+    };
+    let f = || -> Result<(), Error<&str>> { Err(Error::transient("err")) };
+    let res = Retry::new(Constant::new(Duration::from_secs(1)))
+        .with_sleep(sleep)
+        .with_clock(clock.clone())
+        .retry_cancellable(f, &cancel);
+
+    assert_eq!(res, Err(CancelError::Cancelled(Error::transient("err"))));
+    assert_eq!(sleeper.sleeps(), vec![Duration::from_secs(1); 2]);
+    assert_eq!(clock.elapsed(), Duration::from_secs(2));
+}
+
+#[test]
+fn deadline_in_virtual_time() {
+    let clock = MockClock::new();
+    let sleeper = MockSleeper::new(clock.clone());
+
+    let mut attempts = 0;
+    let f = || -> Result<(), Error<&str>> {
+        attempts += 1;
+        clock.advance(Duration::from_secs(1));
+        Err(Error::transient("err"))
+    };
+    let res = Retry::new(Constant::new(Duration::from_secs(1)))
+        .with_sleep(sleeper.clone())
+        .with_clock(clock.clone())
+        .with_deadline(Duration::from_millis(5500))
+        .retry(f);
+
+    // The last delay would pass the deadline, so the loop gives up without waiting.
+    assert_eq!(res, Err(Error::transient("err")));
+    assert_eq!(attempts, 3);
+    assert_eq!(sleeper.sleeps(), vec![Duration::from_secs(1); 2]);
+    assert_eq!(clock.elapsed(), Duration::from_secs(5));
+}
+
+#[test]
+fn unreachable_deadline() {
+    let clock = MockClock::new();
+    let sleeper = MockSleeper::new(clock.clone());
+
+    let f = || -> Result<(), Error<&str>> { Err(Error::transient("err")) };
+    let res = Retry::new(Constant::new(Duration::from_secs(1)).with_max_retries(2))
+        .with_sleep(sleeper.clone())
+        .with_clock(clock.clone())
+        .with_deadline(Duration::MAX)
+        .retry_report(f);
+
+    assert_eq!(res.unwrap_err().reason, StopReason::Exhausted);
+    assert_eq!(sleeper.sleeps(), vec![Duration::from_secs(1); 2]);
+}
+
+#[cfg(feature = "futures")]
+#[test]
+fn future_deadline_in_virtual_time() {
+    use backoff::future::retry_with_sleeper;
+    use backoff::DeadlineError;
+
+    let clock = MockClock::new();
+    let sleeper = MockSleeper::new(clock.clone());
+
+    let mut attempts = 0;
+    let operation = || {
+        attempts += 1;
+        let clock = clock.clone();
+        async move {
+            clock.advance(Duration::from_secs(1));
+            Err::<(), _>(Error::transient("err"))
+        }
+    };
+    let retry = retry_with_sleeper(
+        sleeper.clone(),
+        Constant::new(Duration::from_secs(1)),
+        operation,
+    )
+    .with_clock(clock.clone())
+    .with_deadline(Duration::from_millis(5500));
+
+    // The last delay would pass the deadline, so the loop gives up without waiting.
+    let res = futures_executor::block_on(retry);
+    assert_eq!(res, Err(DeadlineError::Exceeded(Some("err"))));
+    assert_eq!(attempts, 3);
+    assert_eq!(sleeper.sleeps(), vec![Duration::from_secs(1); 2]);
+    assert_eq!(clock.elapsed(), Duration::from_secs(5));
+}
+
+#[cfg(feature = "futures")]
+#[test]
+fn future_deadline_bounds_async_hooks() {
+    use backoff::classify::Decision;
+    use backoff::future::retry_with_sleeper;
+    use backoff::DeadlineError;
+    use std::future::{pending, ready};
+
+    let clock = MockClock::new();
+    let sleeper = MockSleeper::new(clock.clone());
+    let backoff = || Constant::new(Duration::from_secs(1));
+    let operation = || ready(Err::<(), _>(Error::transient("err")));
+
+    // Deciding takes two seconds, leaving no time for the next delay.
+    let classify = |_: &&str| {
+        let clock = clock.clone();
+        async move {
+            clock.advance(Duration::from_secs(2));
+            Decision::Transient
+        }
+    };
+    let retry = retry_with_sleeper(sleeper.clone(), backoff(), operation)
+        .with_clock(clock.clone())
+        .with_async_classifier(classify)
+        .with_deadline(Duration::from_millis(2500));
+    let res = futures_executor::block_on(retry);
+    assert_eq!(res, Err(DeadlineError::Exceeded(Some("err"))));
+    assert!(sleeper.sleeps().is_empty());
+
+    // Deciding never completes, the deadline gives up on it.
+    let clock = MockClock::new();
+    let sleeper = MockSleeper::new(clock.clone());
+    let retry = retry_with_sleeper(sleeper.clone(), backoff(), operation)
+        .with_clock(clock.clone())
+        .with_async_classifier(|_: &&str| pending::<Decision>())
+        .with_deadline(Duration::from_secs(5));
+    let res = futures_executor::block_on(retry);
+    assert_eq!(res, Err(DeadlineError::Exceeded(Some("err"))));
+    assert_eq!(clock.elapsed(), Duration::from_secs(5));
+
+    // Notifying never completes, the error was already passed to the notification.
+    let clock = MockClock::new();
+    let sleeper = MockSleeper::new(clock.clone());
+    let retry = retry_with_sleeper(sleeper.clone(), backoff(), operation)
+        .with_clock(clock.clone())
+        .with_async_notify(|_: &&str, _| pending::<()>())
+        .with_deadline(Duration::from_secs(5));
+    let res = futures_executor::block_on(retry);
+    assert_eq!(res, Err(DeadlineError::Exceeded(None)));
+    assert_eq!(clock.elapsed(), Duration::from_secs(5));
+}
+
+#[cfg(feature = "futures")]
+#[test]
+fn future_unreachable_deadline() {
+    use backoff::future::retry_with_sleeper;
+    use backoff::DeadlineError;
+
+    let sleeper = MockSleeper::new(MockClock::new());
+    let operation = || async { Err::<(), _>(Error::transient("err")) };
+    let retry = retry_with_sleeper(
+        sleeper.clone(),
+        Constant::new(Duration::from_secs(1)).with_max_retries(2),
+        operation,
+    )
+    .with_deadline(Duration::MAX);
+
+    let res = futures_executor::block_on(retry);
+    assert_eq!(res, Err(DeadlineError::Failed("err")));
+    assert_eq!(sleeper.sleeps(), vec![Duration::from_secs(1); 2]);
+}
+
+#[cfg(feature = "futures")]
+#[test]
+fn future_deadline_without_error() {
+    use backoff::future::retry_notify_with_sleeper;
+    use backoff::{DeadlineError, RetryError};
+    use std::future::{pending, ready};
+
+    #[derive(Clone, Default)]
+    struct Outcome(Arc<Mutex<Vec<(u32, Duration)>>>);
+
+    impl Notify<&'static str> for Outcome {
+        fn notify(&mut self, _: &'static str, _: Duration) {}
+
+        fn on_give_up(&mut self, _: &RetryError<&'static str>) {
+            panic!("no error to give up with");
+        }
+
+        fn on_deadline(&mut self, attempts: u32, elapsed: Duration) {
+            self.0.lock().unwrap().push((attempts, elapsed));
+        }
+    }
+
+    // The first attempt never completes, the deadline cancels it.
+    let clock = MockClock::new();
+    let outcome = Outcome::default();
+    let retry = retry_notify_with_sleeper(
+        MockSleeper::new(clock.clone()),
+        Constant::new(Duration::from_secs(1)),
+        pending::<Result<(), Error<&str>>>,
+        outcome.clone(),
+    )
+    .with_clock(clock.clone())
+    .with_deadline(Duration::from_secs(5));
+    let res = futures_executor::block_on(retry);
+    assert_eq!(res, Err(DeadlineError::Exceeded(None)));
+    assert_eq!(
+        *outcome.0.lock().unwrap(),
+        vec![(1, Duration::from_secs(5))]
+    );
+
+    // The error of the first attempt was notified, the notification never completes.
+    let clock = MockClock::new();
+    let outcome = Outcome::default();
+    let retry = retry_notify_with_sleeper(
+        MockSleeper::new(clock.clone()),
+        Constant::new(Duration::from_secs(1)),
+        || ready(Err::<(), _>(Error::transient("err"))),
+        outcome.clone(),
+    )
+    .with_clock(clock.clone())
+    .with_async_notify(|_: &&str, _| pending::<()>())
+    .with_deadline(Duration::from_secs(5));
+    let res = futures_executor::block_on(retry);
+    assert_eq!(res, Err(DeadlineError::Exceeded(None)));
+    assert_eq!(
+        *outcome.0.lock().unwrap(),
+        vec![(1, Duration::from_secs(5))]
+    );
+}
@@ -0,0 +1,179 @@
+#![cfg(feature = "tracing")]
+
+use backoff::backoff::{BackoffExt, Zero};
+use backoff::Error;
+
+use std::fmt::{self, Write};
+use std::sync::{Arc, Mutex};
+
+use tracing_0_1::field::{Field, Visit};
+use tracing_0_1::span::{Attributes, Id, Record};
+use tracing_0_1::{Event, Metadata, Subscriber};
+
+// Records the events as `span{fields} > ... > message fields` lines, listing the current span
+// and its parents.
+#[derive(Clone, Default)]
+struct Recorder {
+    // The name and fields of the spans, and their parents, by id.
+    spans: Arc<Mutex<Vec<SpanData>>>,
+    stack: Arc<Mutex<Vec<u64>>>,
+    lines: Arc<Mutex<Vec<String>>>,
+}
+
+type SpanData = (String, Option<u64>);
+
+struct Fields(String);
+
+impl Visit for Fields {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if !self.0.is_empty() {
+            self.0.push(' ');
+        }
+        if field.name() == "message" {
+            write!(self.0, "{:?}", value).unwrap();
+        } else {
+            write!(self.0, "{}={:?}", field.name(), value).unwrap();
+        }
+    }
+}
+
+impl Subscriber for Recorder {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        let mut fields = Fields(String::new());
+        span.record(&mut fields);
+        let name = format!("{}{{{}}}", span.metadata().name(), fields.0);
+
+        let parent = match span.parent() {
+            Some(parent) => Some(parent.into_u64()),
+            None if span.is_contextual() => self.stack.lock().unwrap().last().copied(),
+            None => None,
+        };
+
+        let mut spans = self.spans.lock().unwrap();
+        spans.push((name, parent));
+        Id::from_u64(spans.len() as u64)
+    }
+
+    fn record(&self, _: &Id, _: &Record<'_>) {}
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let spans = self.spans.lock().unwrap();
+        let mut scope = Vec::new();
+        let mut current = self.stack.lock().unwrap().last().copied();
+        while let Some(id) = current {
+            let (name, parent) = &spans[id as usize - 1];
+            scope.push(name.as_str());
+            current = *parent;
+        }
+
+        let mut line = String::new();
+        for name in scope.iter().rev() {
+            write!(line, "{} > ", name).unwrap();
+        }
+        let mut fields = Fields(String::new());
+        event.record(&mut fields);
+        line.push_str(&fields.0);
+        self.lines.lock().unwrap().push(line);
+    }
+
+    fn enter(&self, span: &Id) {
+        self.stack.lock().unwrap().push(span.into_u64());
+    }
+
+    fn exit(&self, span: &Id) {
+        let mut stack = self.stack.lock().unwrap();
+        let pos = stack.iter().rposition(|id| *id == span.into_u64()).unwrap();
+        stack.remove(pos);
+    }
+}
+
+#[test]
+fn retry() {
+    let recorder = Recorder::default();
+    let mut attempts = 0;
+    let f = || -> Result<u32, Error<&str>> {
+        attempts += 1;
+        tracing_0_1::info!("attempt {}", attempts);
+        match attempts {
+            1 => Err(Error::transient("unavailable")),
+            _ => Ok(attempts),
+        }
+    };
+
+    let res = tracing_0_1::subscriber::with_default(recorder.clone(), || {
+        backoff::retry(Zero {}.with_max_retries(1), f)
+    });
+    assert_eq!(res.unwrap(), 2);
+
+    let lines = recorder.lines.lock().unwrap();
+    assert_eq!(lines.len(), 4, "{:#?}", lines);
+    assert_eq!(lines[0], "retry{} > attempt{attempt=1} > attempt 1");
+    assert_eq!(
+        lines[1],
+        "retry{} > attempt{attempt=1} > attempt failed, retrying \
+         error=unavailable delay=0ns attempt=1"
+    );
+    assert_eq!(lines[2], "retry{} > attempt{attempt=2} > attempt 2");
+    assert!(lines[3]
+        .starts_with("retry{} > attempt{attempt=2} > succeeded after retries attempts=2 elapsed="));
+}
+
+#[test]
+fn give_up() {
+    let recorder = Recorder::default();
+    let f = || -> Result<(), Error<&str>> { Err(Error::permanent("not found")) };
+
+    let res =
+        tracing_0_1::subscriber::with_default(recorder.clone(), || backoff::retry(Zero {}, f));
+    assert_eq!(res, Err(Error::permanent("not found")));
+
+    let lines = recorder.lines.lock().unwrap();
+    assert_eq!(lines.len(), 1, "{:#?}", lines);
+    assert!(lines[0].starts_with(
+        "retry{} > attempt{attempt=1} > giving up error=not found reason=permanent error \
+         attempts=1 elapsed="
+    ));
+}
+
+#[cfg(all(feature = "futures", feature = "testing"))]
+#[test]
+fn future_retry() {
+    use backoff::future::retry_with_sleeper;
+    use backoff::testing::{MockClock, MockSleeper};
+    use std::future::ready;
+
+    let recorder = Recorder::default();
+    let mut attempts = 0;
+    let res = tracing_0_1::subscriber::with_default(recorder.clone(), || {
+        futures_executor::block_on(retry_with_sleeper(
+            MockSleeper::new(MockClock::new()),
+            Zero {}.with_max_retries(1),
+            || {
+                attempts += 1;
+                tracing_0_1::info!("attempt {}", attempts);
+                ready(Err::<(), _>(Error::transient("unavailable")))
+            },
+        ))
+    });
+    assert_eq!(res, Err("unavailable"));
+
+    let lines = recorder.lines.lock().unwrap();
+    assert_eq!(lines.len(), 4, "{:#?}", lines);
+    assert_eq!(lines[0], "retry{} > attempt{attempt=1} > attempt 1");
+    assert_eq!(
+        lines[1],
+        "retry{} > attempt{attempt=1} > attempt failed, retrying \
+         error=unavailable delay=0ns attempt=1"
+    );
+    assert_eq!(lines[2], "retry{} > attempt{attempt=2} > attempt 2");
+    assert!(lines[3].starts_with(
+        "retry{} > attempt{attempt=2} > giving up error=unavailable \
+         reason=retries exhausted attempts=2 elapsed="
+    ));
+}